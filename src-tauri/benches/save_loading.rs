@@ -0,0 +1,179 @@
+//! Benchmarks for loading and querying a large save. A synthetic save of about
+//! 50 MB, roughly the size of a late-game file, is generated once per run.
+//!
+//! Run with `cargo bench --bench save_loading`.
+
+use app_lib::models::has_profession;
+use app_lib::utils::character_index::{CharacterIndex, CharacterList};
+use app_lib::utils::save_file::{encode_save_file, read_save_file_stamped};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const TARGET_SIZE: usize = 50 * 1024 * 1024;
+
+const PROFESSIONS: [&str; 10] = [
+    "Actor",
+    "Director",
+    "Producer",
+    "Scriptwriter",
+    "FilmEditor",
+    "Composer",
+    "Cinematographer",
+    "Agent",
+    "CptHR",
+    "LieutScript",
+];
+
+const WHITE_TAGS: [&str; 4] = ["ACTION", "COMEDY", "DRAMA", "ART"];
+
+fn synthetic_character(id: usize) -> Value {
+    let profession = PROFESSIONS[id % PROFESSIONS.len()];
+    let white_tags: serde_json::Map<String, Value> = WHITE_TAGS
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let value = ((id + i) % 100) as f64 / 10.0;
+            let entry = json!({
+                "id": tag,
+                "dateAdded": "1929-03-01T00:00:00",
+                "movieId": 0,
+                "value": value.to_string(),
+                "overallValues": [
+                    { "movieId": 0, "sourceType": 0, "value": value.to_string(), "dateAdded": "1929-03-01T00:00:00" },
+                    { "movieId": id % 500, "sourceType": 2, "value": "0.5", "dateAdded": "1931-07-15T00:00:00" }
+                ]
+            });
+            (tag.to_string(), entry)
+        })
+        .collect();
+
+    json!({
+        "id": id,
+        "firstNameId": (id % 4000).to_string(),
+        "lastNameId": ((id * 7) % 4000).to_string(),
+        "customName": null,
+        "birthDate": format!("{:02}-{:02}-18{:02}", 1 + id % 28, 1 + id % 12, 60 + id % 40),
+        "gender": id % 2,
+        "studioId": if id % 3 == 0 { json!("PL") } else { Value::Null },
+        "portraitBaseId": id % 300,
+        "mood": 0.5,
+        "attitude": 0.25,
+        "selfEsteem": "0.5",
+        "readiness": 1.0,
+        "limit": 0.9,
+        "state": 0,
+        "professions": { profession: format!("0.{}", id % 100) },
+        "whiteTagsNEW": white_tags,
+        "contract": {
+            "contractType": 1,
+            "amount": 2,
+            "startAmount": 2,
+            "initialFee": "1000",
+            "monthlySalary": "250",
+            "weightToSalary": "0.8",
+            "dateOfSigning": "1930-01-01T00:00:00"
+        },
+        "labels": ["TALENTED", "SCANDALOUS"],
+        "aSins": [],
+        "activeOrPlannedMovies": [],
+        "isShady": id % 11 == 0,
+        "isOnTheHook": false,
+        "mayHaveSins": null,
+        "BonusCardMoney": 0,
+        "BonusCardInfluencePoints": 0
+    })
+}
+
+fn synthetic_save() -> Value {
+    let character_size = serde_json::to_vec(&synthetic_character(1)).unwrap().len();
+    let characters: Vec<Value> = (1..=TARGET_SIZE / character_size)
+        .map(synthetic_character)
+        .collect();
+    json!({
+        "stateJson": {
+            "timePassed": "1000.00:00:00",
+            "studioName": "Benchmark Pictures",
+            "budget": 1_000_000,
+            "cash": 250_000,
+            "reputation": 0.5,
+            "influence": 100,
+            "characters": characters
+        }
+    })
+}
+
+fn write_synthetic_save(data: &Value) -> (PathBuf, u64) {
+    let path = std::env::temp_dir().join("hollywood-animal-bench-save.json");
+    let content = encode_save_file(data).unwrap();
+    fs::write(&path, &content).unwrap();
+    (path, content.len() as u64)
+}
+
+fn characters(data: &Value) -> &[Value] {
+    data["stateJson"]["characters"].as_array().unwrap()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let (path, size) = write_synthetic_save(&synthetic_save());
+    let path = path.to_str().unwrap().to_string();
+
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(size));
+    // What loading did before: the whole file as a string, then a parse.
+    group.bench_function("read_to_string", |b| {
+        b.iter(|| {
+            let content = fs::read_to_string(&path).unwrap();
+            serde_json::from_str::<Value>(content.trim_start_matches('\u{feff}')).unwrap()
+        })
+    });
+    group.bench_function("streaming_with_hash", |b| {
+        b.iter(|| read_save_file_stamped(&path).unwrap())
+    });
+    group.finish();
+
+    let _ = fs::remove_file(&path);
+}
+
+fn bench_index(c: &mut Criterion) {
+    let data = synthetic_save();
+
+    let mut group = c.benchmark_group("index");
+    group.sample_size(10);
+    group.bench_function("build", |b| {
+        b.iter(|| CharacterIndex::build(characters(&data)))
+    });
+    group.finish();
+}
+
+fn bench_get_persons(c: &mut Criterion) {
+    let data = Arc::new(synthetic_save());
+    let index = CharacterIndex::build(characters(&data));
+
+    let mut group = c.benchmark_group("get_persons");
+    group.sample_size(20);
+    // What get_persons did before: scan, clone every match, then serialize.
+    group.bench_function("scan_and_clone", |b| {
+        b.iter(|| {
+            let persons: Vec<Value> = characters(&data)
+                .iter()
+                .filter(|c| has_profession(c, "Actor"))
+                .cloned()
+                .collect();
+            serde_json::to_vec(&persons).unwrap()
+        })
+    });
+    group.bench_function("indexed_borrowed", |b| {
+        b.iter(|| {
+            let positions = index.with_profession("Actor").to_vec();
+            serde_json::to_vec(&CharacterList::new(data.clone(), positions)).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_index, bench_get_persons);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+use std::fs;
+
+use crate::error::{AppError, AppResult};
+use crate::models::TraitCatalogue;
+use crate::scripting::run_script;
+use crate::utils::paths::find_game_path;
+use crate::utils::restore_number_formats;
+use crate::utils::save_file::{read_save_file, write_save_file};
+
+const SCRIPT_FLAG: &str = "--script";
+const USAGE: &str =
+    "Usage: HollywoodAnimalSE --script <file.rhai> --save <save file> [--out <path>] [--dry-run]";
+
+struct ScriptArgs {
+    script: String,
+    save: String,
+    out: Option<String>,
+    dry_run: bool,
+}
+
+pub fn is_script_invocation(args: &[String]) -> bool {
+    args.iter().any(|a| a == SCRIPT_FLAG)
+}
+
+/// Release builds on Windows use the GUI subsystem and start without a console,
+/// which would swallow the script's output and errors. Attaching to the console of
+/// the shell that started the program lets `println!` and `eprintln!` reach it.
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails harmlessly when there is no parent console or one is already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
+
+/// Runs a script against a save file without starting the GUI. The save is written
+/// back in place unless `--out` or `--dry-run` is given.
+pub fn run(args: &[String]) -> AppResult<()> {
+    let args = parse_args(args)?;
+    let source = fs::read_to_string(&args.script)?;
+    let original = read_save_file(&args.save)?;
+    let mut data = original.clone();
+
+    let traits = TraitCatalogue::load(find_game_path().as_deref());
+    let run = run_script(&mut data, &source, traits)?;
+    for line in &run.output {
+        println!("{}", line);
+    }
+    if !run.result.is_empty() {
+        println!("{}", run.result);
+    }
+
+    if !args.dry_run {
+        restore_number_formats(&mut data, &original);
+        write_save_file(args.out.as_deref().unwrap_or(&args.save), &data)?;
+    }
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> AppResult<ScriptArgs> {
+    let mut script = None;
+    let mut save = None;
+    let mut out = None;
+    let mut dry_run = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            SCRIPT_FLAG => script = iter.next().cloned(),
+            "--save" => save = iter.next().cloned(),
+            "--out" => out = iter.next().cloned(),
+            "--dry-run" => dry_run = true,
+            other => {
+                return Err(AppError::Invalid(format!(
+                    "Unknown argument {}\n{}",
+                    other, USAGE
+                )))
+            }
+        }
+    }
+
+    let usage = || AppError::Invalid(USAGE.to_string());
+    Ok(ScriptArgs {
+        script: script.ok_or_else(usage)?,
+        save: save.ok_or_else(usage)?,
+        out,
+        dry_run,
+    })
+}
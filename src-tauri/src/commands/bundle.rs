@@ -0,0 +1,266 @@
+use serde_json::Value;
+use std::fs;
+use tauri::State;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    get_leadership_profession, leadership_replacement, step_down_from_leadership,
+    strip_movie_history, BundledPerson, PersonBundle, PERSON_BUNDLE_VERSION,
+};
+use crate::state::AppState;
+use crate::utils::game_data::load_language_strings;
+use crate::utils::{json_id_string, SaveDataExt};
+
+const DEFAULT_LANGUAGE_CODE: &str = "ENG";
+
+/// Writes the characters to a bundle. Leadership roles stay with the save, so
+/// captains and lieutenants are exported with `base_profession` instead.
+#[tauri::command]
+pub fn export_persons(
+    person_ids: Vec<String>,
+    path: String,
+    language_code: Option<String>,
+    base_profession: Option<String>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<usize> {
+    let language_code = language_code.unwrap_or_else(|| DEFAULT_LANGUAGE_CODE.to_string());
+    let names = load_language_strings(&state.ensure_game_path()?, &language_code)?;
+
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let persons = person_ids
+        .iter()
+        .map(|id| {
+            let character = snapshot.character(id)?;
+            Ok(BundledPerson {
+                first_name: resolve_name(character, "firstNameId", &names),
+                last_name: resolve_name(character, "lastNameId", &names),
+                character: portable_character(character, base_profession.as_deref())?,
+            })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    let bundle = PersonBundle {
+        version: PERSON_BUNDLE_VERSION,
+        language_code,
+        persons,
+    };
+
+    let json = serde_json::to_string_pretty(&bundle)?;
+    fs::write(&path, json)?;
+    Ok(bundle.persons.len())
+}
+
+#[tauri::command]
+pub fn import_persons(
+    path: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<String>> {
+    let content = fs::read_to_string(&path)?;
+    let bundle: PersonBundle = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| AppError::Invalid(format!("Failed to parse character bundle: {}", e)))?;
+    if bundle.version > PERSON_BUNDLE_VERSION {
+        return Err(AppError::Invalid(format!(
+            "Character bundle version {} is newer than supported version {}",
+            bundle.version, PERSON_BUNDLE_VERSION
+        )));
+    }
+
+    let names = load_language_strings(&state.ensure_game_path()?, &bundle.language_code)?;
+
+    for person in &bundle.persons {
+        validate_bundled_character(&person.character)?;
+    }
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        let characters = data.characters_mut()?;
+        let first_id = next_character_id(characters);
+
+        let mut imported = Vec::new();
+        for (new_id, person) in (first_id..).zip(bundle.persons) {
+            let mut character = person.character;
+            character["id"] = serde_json::json!(new_id);
+            remap_names(
+                &mut character,
+                person.first_name.as_deref(),
+                person.last_name.as_deref(),
+                &names,
+            );
+
+            imported.push(json_id_string(&character["id"]));
+            characters.push(character);
+        }
+        Ok(imported)
+    })
+}
+
+/// Copies a character from one open save into another under a fresh id. Both
+/// saves come from the same game, so name ids stay valid and are kept as is.
+#[tauri::command]
+pub fn copy_person_between_saves(
+    person_id: String,
+    from_handle: String,
+    to_handle: String,
+    base_profession: Option<String>,
+    state: State<AppState>,
+) -> AppResult<String> {
+    let character = portable_character(
+        state.snapshot(Some(&from_handle))?.character(&person_id)?,
+        base_profession.as_deref(),
+    )?;
+
+    state.with_save_data_mut(Some(&to_handle), |data| {
+        let characters = data.characters_mut()?;
+        let mut character = character;
+        character["id"] = serde_json::json!(next_character_id(characters));
+
+        let new_id = json_id_string(&character["id"]);
+        characters.push(character);
+        Ok(new_id)
+    })
+}
+
+fn next_character_id(characters: &[Value]) -> i64 {
+    characters
+        .iter()
+        .filter_map(|c| c.get("id").map(json_id_string))
+        .filter_map(|id| id.parse::<i64>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+fn resolve_name(character: &Value, field: &str, names: &[String]) -> Option<String> {
+    let index: usize = character.get(field)?.as_str()?.parse().ok()?;
+    names.get(index).cloned()
+}
+
+/// Strips the parts of a character that only make sense in its original save:
+/// employer, contract, movie assignments and the white tag history earned on that
+/// save's movies. A leadership role is replaced with `base_profession`.
+fn portable_character(character: &Value, base_profession: Option<&str>) -> AppResult<Value> {
+    let base_profession = leadership_replacement(character, base_profession)?;
+
+    let mut character = character.clone();
+    character["studioId"] = Value::Null;
+    character["contract"] = Value::Null;
+    character["activeOrPlannedMovies"] = serde_json::json!([]);
+    if let Some(base) = base_profession {
+        step_down_from_leadership(&mut character, base);
+    }
+    strip_movie_history(&mut character);
+    Ok(character)
+}
+
+/// Bundled characters arrive as free agents, so they need a profession and can't
+/// hold a leadership role.
+fn validate_bundled_character(character: &Value) -> AppResult<()> {
+    if !character.is_object() {
+        return Err(AppError::Invalid(
+            "Character bundle contains an invalid character".to_string(),
+        ));
+    }
+    let has_profession = character
+        .get("professions")
+        .and_then(|p| p.as_object())
+        .is_some_and(|p| !p.is_empty());
+    if !has_profession {
+        return Err(AppError::Invalid(
+            "Character bundle contains a character without a profession".to_string(),
+        ));
+    }
+    if let Some(role) = get_leadership_profession(character) {
+        return Err(AppError::Invalid(format!(
+            "Character bundle contains a character holding the {} role",
+            role
+        )));
+    }
+    Ok(())
+}
+
+/// Points the name ids at the matching entries of the target localization file.
+/// Names that file doesn't contain are kept as a custom name instead.
+fn remap_names(
+    character: &mut Value,
+    first_name: Option<&str>,
+    last_name: Option<&str>,
+    names: &[String],
+) {
+    let mut unresolved = false;
+    for (field, name) in [("firstNameId", first_name), ("lastNameId", last_name)] {
+        let Some(name) = name else { continue };
+        match names.iter().position(|n| n == name) {
+            Some(index) => character[field] = Value::String(index.to_string()),
+            None => unresolved = true,
+        }
+    }
+
+    let has_custom_name = character
+        .get("customName")
+        .and_then(|c| c.as_str())
+        .is_some_and(|c| !c.is_empty());
+    if unresolved && !has_custom_name {
+        let full_name = [first_name, last_name]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        character["customName"] = Value::String(full_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn character(professions: Value) -> Value {
+        json!({
+            "id": 7,
+            "studioId": "PL",
+            "professions": professions,
+            "contract": { "amount": 2 },
+            "activeOrPlannedMovies": [40]
+        })
+    }
+
+    #[test]
+    fn portable_character_drops_the_save_specific_parts() {
+        let portable = portable_character(&character(json!({ "Actor": 0.5 })), None).unwrap();
+
+        assert_eq!(portable["studioId"], Value::Null);
+        assert_eq!(portable["contract"], Value::Null);
+        assert_eq!(portable["activeOrPlannedMovies"], json!([]));
+        assert_eq!(portable["professions"], json!({ "Actor": 0.5 }));
+        assert!(validate_bundled_character(&portable).is_ok());
+    }
+
+    #[test]
+    fn leadership_holder_is_exported_with_a_base_profession() {
+        let executive = character(json!({ "CptLawyer": 0.8 }));
+        assert!(portable_character(&executive, None).is_err());
+
+        let portable = portable_character(&executive, Some("Scriptwriter")).unwrap();
+        assert_eq!(portable["professions"], json!({ "Scriptwriter": 0.0 }));
+        assert!(validate_bundled_character(&portable).is_ok());
+    }
+
+    #[test]
+    fn import_refuses_characters_without_a_usable_profession() {
+        assert!(validate_bundled_character(&json!("not a character")).is_err());
+        assert!(validate_bundled_character(&character(json!({}))).is_err());
+        assert!(validate_bundled_character(&json!({ "id": 7 })).is_err());
+        assert!(validate_bundled_character(&character(json!({ "CptPR": 0.5 }))).is_err());
+    }
+
+    #[test]
+    fn next_character_id_follows_numeric_and_string_ids() {
+        let characters = [
+            json!({ "id": 3 }),
+            json!({ "id": "12" }),
+            json!({ "id": "x" }),
+        ];
+        assert_eq!(next_character_id(&characters), 13);
+        assert_eq!(next_character_id(&[]), 1);
+    }
+}
@@ -0,0 +1,32 @@
+use tauri::State;
+
+use crate::error::AppResult;
+use crate::models::{
+    DepartmentCatalogue, ResourceCatalogue, StudioLogoCatalogue, TitanCatalogue, TraitCatalogue,
+};
+use crate::state::AppState;
+
+#[tauri::command]
+pub fn get_trait_catalogue(state: State<AppState>) -> AppResult<TraitCatalogue> {
+    Ok(TraitCatalogue::load(state.get_game_path().as_deref()))
+}
+
+#[tauri::command]
+pub fn get_studio_logos(state: State<AppState>) -> AppResult<StudioLogoCatalogue> {
+    Ok(StudioLogoCatalogue::load(state.get_game_path().as_deref()))
+}
+
+#[tauri::command]
+pub fn get_resource_catalogue(state: State<AppState>) -> AppResult<ResourceCatalogue> {
+    Ok(ResourceCatalogue::load(state.get_game_path().as_deref()))
+}
+
+#[tauri::command]
+pub fn get_titan_catalogue(state: State<AppState>) -> AppResult<TitanCatalogue> {
+    Ok(TitanCatalogue::load(state.get_game_path().as_deref()))
+}
+
+#[tauri::command]
+pub fn get_department_catalogue(state: State<AppState>) -> AppResult<DepartmentCatalogue> {
+    Ok(DepartmentCatalogue::load(state.get_game_path().as_deref()))
+}
@@ -1,17 +1,21 @@
 use tauri::State;
 
+use crate::error::{AppError, AppResult};
 use crate::models::{CompetitorStudio, CompetitorUpdate};
 use crate::state::AppState;
-use crate::utils::{get_state_json_mut, SaveDataExt};
+use crate::utils::{get_state_json_mut, set_number, NumberFormat, SaveDataExt};
 
 #[tauri::command]
-pub fn get_competitors(state: State<AppState>) -> Result<Vec<CompetitorStudio>, String> {
-    state.with_save_data(|data| {
+pub fn get_competitors(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<CompetitorStudio>> {
+    state.with_save_data(handle.as_deref(), |data| {
         let state_json = data.state_json()?;
         let competitors = state_json
             .get("competitorStudios")
             .and_then(|c| c.as_object())
-            .ok_or("Missing competitorStudios")?;
+            .ok_or_else(|| AppError::missing("stateJson.competitorStudios"))?;
 
         let mut result = Vec::new();
         for (id, studio) in competitors.iter() {
@@ -44,28 +48,39 @@ pub fn get_competitors(state: State<AppState>) -> Result<Vec<CompetitorStudio>,
 pub fn update_competitor(
     competitor_id: String,
     update: CompetitorUpdate,
+    handle: Option<String>,
     state: State<AppState>,
-) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
+) -> AppResult<()> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
         let state_json = get_state_json_mut(data)?;
 
         let competitors = state_json
             .get_mut("competitorStudios")
             .and_then(|c| c.as_object_mut())
-            .ok_or("Missing competitorStudios")?;
+            .ok_or_else(|| AppError::missing("stateJson.competitorStudios"))?;
 
         let studio = competitors
             .get_mut(&competitor_id)
-            .ok_or_else(|| format!("Competitor {} not found", competitor_id))?;
+            .ok_or_else(|| AppError::not_found("Competitor", &competitor_id))?;
 
         if let Some(last_budget) = update.last_budget {
-            studio["lastBudget"] = serde_json::json!(last_budget);
+            set_number(
+                studio,
+                "lastBudget",
+                last_budget as f64,
+                NumberFormat::Integer,
+            );
         }
         if let Some(ip) = update.ip {
-            studio["ip"] = serde_json::json!(ip);
+            set_number(studio, "ip", ip as f64, NumberFormat::Integer);
         }
         if let Some(budget_cheats) = update.budget_cheats_remaining {
-            studio["budgetCheatsRemaining"] = serde_json::json!(budget_cheats);
+            set_number(
+                studio,
+                "budgetCheatsRemaining",
+                budget_cheats as f64,
+                NumberFormat::Integer,
+            );
         }
 
         Ok(())
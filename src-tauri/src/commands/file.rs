@@ -1,26 +1,42 @@
 use serde_json::Value;
-use std::fs;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
-use crate::models::{count_profession, SaveInfo};
+use crate::error::{AppError, AppResult};
+use crate::models::{OpenSave, SaveFileChanged, SaveInfo};
 use crate::state::AppState;
-use crate::utils::{calculate_current_date, paths, SaveDataExt, DEFAULT_TIME_PASSED};
+use crate::utils::character_index::CharacterIndex;
+use crate::utils::game_data::load_language_strings;
+use crate::utils::save_file::read_save_file_stamped;
+use crate::utils::{calculate_current_date, json_number, paths, SaveDataExt, DEFAULT_TIME_PASSED};
+
+pub const SAVE_FILE_CHANGED_EVENT: &str = "save-file-changed";
 
 #[tauri::command]
-pub fn load_save_file(path: String, state: State<AppState>) -> Result<SaveInfo, String> {
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let content = content.trim_start_matches('\u{feff}');
-
-    let save_data: Value = serde_json::from_str(content).map_err(|e| {
-        format!(
-            "Failed to parse JSON: {}. First 100 chars: {}",
-            e,
-            content.chars().take(100).collect::<String>()
-        )
-    })?;
+pub fn load_save_file(path: String, app: AppHandle, state: State<AppState>) -> AppResult<SaveInfo> {
+    let (save_data, stamp) = read_save_file_stamped(&path)?;
+    let index = CharacterIndex::build(save_data.characters()?);
+    let info = save_info(&save_data, &index)?;
+
+    let handle = state.load_save(path, save_data, index, stamp);
+    watch_save(&app, &state, &handle);
+    Ok(SaveInfo { handle, ..info })
+}
 
+/// Summarises the save as it is in memory, including edits and merges that have
+/// not been written to disk yet.
+#[tauri::command]
+pub fn get_save_info(handle: Option<String>, state: State<AppState>) -> AppResult<SaveInfo> {
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let info = save_info(&snapshot.data, snapshot.characters_index()?)?;
+    Ok(SaveInfo {
+        handle: snapshot.handle.clone(),
+        ..info
+    })
+}
+
+/// Builds the save's summary. The handle is left empty for the caller to fill in.
+fn save_info(save_data: &Value, index: &CharacterIndex) -> AppResult<SaveInfo> {
     let state_json = save_data.state_json()?;
-    let characters = save_data.characters()?;
 
     let time_passed = state_json
         .get("timePassed")
@@ -41,42 +57,40 @@ pub fn load_save_file(path: String, state: State<AppState>) -> Result<SaveInfo,
 
     let budget = state_json
         .get("budget")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0);
+        .and_then(json_number)
+        .unwrap_or(0.0) as i64;
 
-    let cash = state_json
-        .get("cash")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0);
+    let cash = state_json.get("cash").and_then(json_number).unwrap_or(0.0) as i64;
 
     let reputation = state_json
         .get("reputation")
-        .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+        .and_then(json_number)
         .unwrap_or(0.0);
 
     let influence = state_json
         .get("influence")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0);
+        .and_then(json_number)
+        .unwrap_or(0.0) as i64;
 
     let studio_logo_id = state_json
         .get("studioLogoId")
         .and_then(|v| v.as_i64())
         .unwrap_or(0);
 
-    let info = SaveInfo {
+    Ok(SaveInfo {
+        handle: String::new(),
         current_date: calculate_current_date(time_passed),
         player_studio_name,
-        actors_count: count_profession(characters, "Actor"),
-        directors_count: count_profession(characters, "Director"),
-        producers_count: count_profession(characters, "Producer"),
-        writers_count: count_profession(characters, "Scriptwriter"),
-        editors_count: count_profession(characters, "FilmEditor"),
-        composers_count: count_profession(characters, "Composer"),
-        cinematographers_count: count_profession(characters, "Cinematographer"),
-        agents_count: count_profession(characters, "Agent"),
-        dept_heads_count: count_profession(characters, "DepartmentHead"),
-        executives_count: count_profession(characters, "Executive"),
+        actors_count: index.with_profession("Actor").len(),
+        directors_count: index.with_profession("Director").len(),
+        producers_count: index.with_profession("Producer").len(),
+        writers_count: index.with_profession("Scriptwriter").len(),
+        editors_count: index.with_profession("FilmEditor").len(),
+        composers_count: index.with_profession("Composer").len(),
+        cinematographers_count: index.with_profession("Cinematographer").len(),
+        agents_count: index.with_profession("Agent").len(),
+        dept_heads_count: index.with_profession("DepartmentHead").len(),
+        executives_count: index.with_profession("Executive").len(),
         movies_count,
         studios_count: 1,
         budget,
@@ -84,24 +98,59 @@ pub fn load_save_file(path: String, state: State<AppState>) -> Result<SaveInfo,
         reputation,
         influence,
         studio_logo_id,
-    };
+    })
+}
+
+/// Writes the save to `path`. Overwriting the file it was loaded from fails with
+/// `MODIFIED_ON_DISK` when the game changed that file in the meantime, unless
+/// `force` is set.
+#[tauri::command]
+pub fn save_save_file(
+    path: String,
+    handle: Option<String>,
+    force: Option<bool>,
+    app: AppHandle,
+    state: State<AppState>,
+) -> AppResult<()> {
+    let (handle, rebound) = state.write_save(handle.as_deref(), &path, force.unwrap_or(false))?;
+    if rebound {
+        watch_save(&app, &state, &handle);
+    }
+    Ok(())
+}
 
-    state.set_save_data(save_data);
-    Ok(info)
+/// Emits `SAVE_FILE_CHANGED_EVENT` whenever the save's file changes on disk. A
+/// save that can't be watched still works, it just isn't reported.
+fn watch_save(app: &AppHandle, state: &AppState, handle: &str) {
+    let app = app.clone();
+    let changed_handle = handle.to_string();
+    let result = state.watch_save(handle, move |path| {
+        let payload = SaveFileChanged {
+            handle: changed_handle.clone(),
+            path: path.to_string(),
+        };
+        if let Err(e) = app.emit(SAVE_FILE_CHANGED_EVENT, payload) {
+            log::warn!("Failed to emit {}: {}", SAVE_FILE_CHANGED_EVENT, e);
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("{}", e);
+    }
 }
 
 #[tauri::command]
-pub fn save_save_file(path: String, state: State<AppState>) -> Result<(), String> {
-    state.with_save_data(|data| {
-        let json = serde_json::to_string(data).map_err(|e| e.to_string())?;
-        let content = format!("\u{feff}{}", json);
-        fs::write(&path, content).map_err(|e| e.to_string())
-    })
+pub fn close_save_file(handle: String, state: State<AppState>) -> AppResult<()> {
+    state.close_save(&handle)
+}
+
+#[tauri::command]
+pub fn list_open_saves(state: State<AppState>) -> AppResult<Vec<OpenSave>> {
+    Ok(state.list_saves())
 }
 
 #[tauri::command]
-pub fn get_current_date(state: State<AppState>) -> Result<String, String> {
-    state.with_save_data(|data| {
+pub fn get_current_date(handle: Option<String>, state: State<AppState>) -> AppResult<String> {
+    state.with_save_data(handle.as_deref(), |data| {
         let time_passed = data
             .state_json()?
             .get("timePassed")
@@ -115,41 +164,23 @@ pub fn get_current_date(state: State<AppState>) -> Result<String, String> {
 pub fn get_language_strings(
     language_code: String,
     state: State<AppState>,
-) -> Result<Vec<String>, String> {
+) -> AppResult<Vec<String>> {
     let game_path = state.ensure_game_path()?;
-    let file_path = paths::language_file_path(&game_path, &language_code);
-
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read language file at '{}': {}", file_path, e))?;
-
-    let data: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse language file: {}", e))?;
-
-    data.get("locStrings")
-        .and_then(|s| s.as_array())
-        .ok_or("Missing locStrings")?
-        .iter()
-        .map(|v| {
-            v.as_str()
-                .map(|s| s.to_string())
-                .ok_or("Invalid locString entry")
-        })
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+    load_language_strings(&game_path, &language_code)
 }
 
 #[tauri::command]
-pub fn get_game_path(state: State<AppState>) -> Result<Option<String>, String> {
+pub fn get_game_path(state: State<AppState>) -> AppResult<Option<String>> {
     Ok(state.get_game_path())
 }
 
 #[tauri::command]
-pub fn set_game_path(path: String, state: State<AppState>) -> Result<(), String> {
+pub fn set_game_path(path: String, state: State<AppState>) -> AppResult<()> {
     if !paths::validate_game_path(&path) {
-        return Err(format!(
+        return Err(AppError::Invalid(format!(
             "Invalid path: {} does not appear to be a Hollywood Animal installation",
             path
-        ));
+        )));
     }
 
     state.set_game_path(path);
@@ -0,0 +1,167 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    overall_value_entries, set_overall_value, upsert_white_tag, white_tag_value, GenreCatalogue,
+    PersonGenre,
+};
+use crate::state::AppState;
+use crate::utils::SaveDataExt;
+
+#[tauri::command]
+pub fn get_genre_catalogue(state: State<AppState>) -> AppResult<GenreCatalogue> {
+    Ok(GenreCatalogue::load(state.get_game_path().as_deref()))
+}
+
+#[tauri::command]
+pub fn get_person_genres(
+    person_id: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<PersonGenre>> {
+    let genres = GenreCatalogue::load(state.get_game_path().as_deref());
+
+    state.with_save_data(handle.as_deref(), |data| {
+        Ok(person_genres(data.character(&person_id)?, &genres))
+    })
+}
+
+/// The white tags of `person` that are genres; ART, COM and other tags are left out.
+fn person_genres(person: &Value, genres: &GenreCatalogue) -> Vec<PersonGenre> {
+    person
+        .get("whiteTagsNEW")
+        .and_then(|w| w.as_object())
+        .map(|tags| {
+            tags.iter()
+                .filter(|(id, _)| genres.contains(id))
+                .map(|(id, tag)| PersonGenre {
+                    genre: id.clone(),
+                    value: white_tag_value(tag),
+                    overall_values: overall_value_entries(tag),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_genre_value(
+    person_id: String,
+    genre: String,
+    value: f64,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    validate_genre(&genre, state.get_game_path().as_deref())?;
+    if value < 0.0 {
+        return Err(AppError::Invalid(format!(
+            "Genre value {} cannot be negative",
+            value
+        )));
+    }
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        upsert_white_tag(data.character_mut(&person_id)?, &genre, value);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_genre_entry(
+    person_id: String,
+    genre: String,
+    movie_id: i64,
+    source_type: i64,
+    value: Option<f64>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    validate_genre(&genre, state.get_game_path().as_deref())?;
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        set_overall_value(
+            data.character_mut(&person_id)?,
+            &genre,
+            movie_id,
+            source_type,
+            value,
+        )
+    })
+}
+
+fn validate_genre(genre: &str, game_path: Option<&str>) -> AppResult<()> {
+    if GenreCatalogue::load(game_path).contains(genre) {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!("Unknown genre {}", genre)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn person() -> Value {
+        json!({
+            "id": 1,
+            "whiteTagsNEW": {
+                "ART": { "id": "ART", "value": 4.0 },
+                "DRAMA": {
+                    "id": "DRAMA",
+                    "value": 9.0,
+                    "overallValues": [
+                        { "movieId": 0, "sourceType": 0, "value": 6.0 },
+                        { "movieId": 12, "sourceType": 1, "value": 3.0 }
+                    ]
+                }
+            }
+        })
+    }
+
+    fn genre_values(person: &Value) -> Vec<(String, f64)> {
+        person_genres(person, &GenreCatalogue::load(None))
+            .into_iter()
+            .map(|g| (g.genre, g.value))
+            .collect()
+    }
+
+    #[test]
+    fn only_catalogue_genres_are_listed() {
+        assert_eq!(genre_values(&person()), [("DRAMA".to_string(), 9.0)]);
+        assert!(validate_genre("DRAMA", None).is_ok());
+        assert!(validate_genre("ART", None).is_err());
+    }
+
+    #[test]
+    fn pruning_a_history_entry_takes_its_share_out_of_the_genre() {
+        let mut person = person();
+
+        set_overall_value(&mut person, "DRAMA", 12, 1, None).unwrap();
+
+        let drama = &person["whiteTagsNEW"]["DRAMA"];
+        assert_eq!(white_tag_value(drama), 6.0);
+        assert_eq!(overall_value_entries(drama).len(), 1);
+        assert!(set_overall_value(&mut person, "DRAMA", 0, 0, None).is_err());
+    }
+
+    #[test]
+    fn explicit_values_set_the_base_entry_and_keep_the_history() {
+        let mut person = person();
+
+        upsert_white_tag(&mut person, "DRAMA", 2.0);
+        upsert_white_tag(&mut person, "COMEDY", 5.0);
+
+        let drama = &person["whiteTagsNEW"]["DRAMA"];
+        let base_and_history: Vec<_> = overall_value_entries(drama)
+            .iter()
+            .map(|e| (e.movie_id, e.value))
+            .collect();
+        assert_eq!(base_and_history, [(0, 2.0), (12, 3.0)]);
+
+        let comedy = &person["whiteTagsNEW"]["COMEDY"];
+        assert_eq!(white_tag_value(comedy), 5.0);
+        assert_eq!(overall_value_entries(comedy).len(), 1);
+    }
+}
@@ -0,0 +1,45 @@
+use tauri::State;
+
+use crate::error::AppResult;
+use crate::models::MergeReport;
+use crate::state::AppState;
+use crate::utils::save_file::read_save_file_stamped;
+use crate::utils::save_merge::merge_saves;
+
+const MERGE_CHECKPOINT_LABEL: &str = "merge";
+
+/// Merges the editor's changes into the version of the save file now on disk,
+/// using the save as it was loaded as the common ancestor. Conflicting values keep
+/// the game's version and are reported for resolution with `apply_json_patch`.
+/// Unless `dry_run` is set, the merged save replaces the loaded one and the
+/// previous state is kept as an undo checkpoint.
+#[tauri::command]
+pub fn merge_with_disk(
+    handle: Option<String>,
+    dry_run: Option<bool>,
+    state: State<AppState>,
+) -> AppResult<MergeReport> {
+    let dry_run = dry_run.unwrap_or(false);
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let (disk, stamp) = read_save_file_stamped(&state.save_path(Some(&snapshot.handle))?)?;
+    let (merged, conflicts) = merge_saves(&snapshot.on_disk, &snapshot.data, &disk);
+
+    let checkpoint = if dry_run {
+        None
+    } else {
+        state.commit_merge(
+            MERGE_CHECKPOINT_LABEL.to_string(),
+            &snapshot,
+            merged,
+            disk,
+            stamp,
+        )?;
+        Some(MERGE_CHECKPOINT_LABEL.to_string())
+    };
+
+    Ok(MergeReport {
+        dry_run,
+        conflicts,
+        checkpoint,
+    })
+}
@@ -1,9 +1,33 @@
+pub mod bundle;
+pub mod catalogue;
 pub mod competitor;
 pub mod file;
+pub mod genre;
+pub mod merge;
 pub mod person;
+pub mod preset;
+pub mod profession;
+pub mod raw;
+pub mod report;
+pub mod roster;
+pub mod script;
 pub mod studio;
+pub mod transfer;
+pub mod white_tag;
 
+pub use bundle::*;
+pub use catalogue::*;
 pub use competitor::*;
 pub use file::*;
+pub use genre::*;
+pub use merge::*;
 pub use person::*;
+pub use preset::*;
+pub use profession::*;
+pub use raw::*;
+pub use report::*;
+pub use roster::*;
+pub use script::*;
 pub use studio::*;
+pub use transfer::*;
+pub use white_tag::*;
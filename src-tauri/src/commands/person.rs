@@ -1,24 +1,30 @@
 use serde_json::Value;
 use tauri::State;
 
-use crate::models::{get_captain_profession, has_profession, PersonUpdate};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    get_captain_profession, get_lieutenant_profession, has_profession, remove_white_tag,
+    upsert_white_tag, PersonUpdate, TraitCatalogue,
+};
 use crate::state::AppState;
-use crate::utils::{json_id_matches, SaveDataExt};
+use crate::utils::character_index::CharacterList;
+use crate::utils::{json_id_matches, set_number, NumberFormat, SaveDataExt};
 
-const DEFAULT_DATE_ADDED: &str = "1929-01-01T00:00:00";
-const BASE_MOVIE_ID: i64 = 0;
-const BASE_SOURCE_TYPE: i64 = 0;
+const MAX_BONUS_CARD_LEVEL: i32 = 4;
+const CONTRACT_AMOUNT_FORMAT: NumberFormat = NumberFormat::Text { decimals: 0 };
 
 #[tauri::command]
-pub fn get_persons(profession: String, state: State<AppState>) -> Result<Vec<Value>, String> {
-    state.with_save_data(|data| {
-        Ok(data
-            .characters()?
-            .iter()
-            .filter(|c| has_profession(c, &profession))
-            .cloned()
-            .collect())
-    })
+pub fn get_persons(
+    profession: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<CharacterList> {
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let positions = snapshot
+        .characters_index()?
+        .with_profession(&profession)
+        .to_vec();
+    Ok(CharacterList::new(snapshot.data, positions))
 }
 
 #[tauri::command]
@@ -26,28 +32,26 @@ pub fn update_person(
     profession: String,
     person_id: String,
     update: PersonUpdate,
+    handle: Option<String>,
     state: State<AppState>,
-) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
+) -> AppResult<()> {
+    let traits = update
+        .add_trait
+        .is_some()
+        .then(|| TraitCatalogue::load(state.get_game_path().as_deref()));
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
         let person = data
             .characters_mut()?
             .iter_mut()
             .find(|c| {
                 has_profession(c, &profession)
-                    && c.get("id").is_some_and(|id| json_id_matches(id, &person_id))
+                    && c.get("id")
+                        .is_some_and(|id| json_id_matches(id, &person_id))
             })
-            .ok_or_else(|| format!("Person {} not found", person_id))?;
-
-        let actual_profession = if profession == "Executive" {
-            get_captain_profession(person)
-                .map(|s| s.to_string())
-                .unwrap_or(profession)
-        } else {
-            profession
-        };
+            .ok_or_else(|| AppError::not_found("Person", &person_id))?;
 
-        apply_updates(person, &actual_profession, &update);
-        Ok(())
+        edit_person(person, &profession, &update, traits.as_ref())
     })
 }
 
@@ -57,9 +61,10 @@ pub fn update_people(
     studio_id: String,
     field: String,
     value: f64,
+    handle: Option<String>,
     state: State<AppState>,
-) -> Result<usize, String> {
-    state.with_save_data_mut(|data| {
+) -> AppResult<usize> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
         let characters = data.characters_mut()?;
         let mut count = 0;
 
@@ -67,16 +72,19 @@ pub fn update_people(
             if !has_profession(person, &profession) {
                 continue;
             }
-            
-            let person_studio = person.get("studioId").and_then(|s| s.as_str()).unwrap_or("");
+
+            let person_studio = person
+                .get("studioId")
+                .and_then(|s| s.as_str())
+                .unwrap_or("");
             if person_studio != studio_id {
                 continue;
             }
 
             match field.as_str() {
-                "mood" => person["mood"] = serde_json::json!(value),
-                "attitude" => person["attitude"] = serde_json::json!(value),
-                "selfEsteem" => person["selfEsteem"] = serde_json::json!(value),
+                "mood" | "attitude" | "selfEsteem" => {
+                    set_number(person, &field, value, NumberFormat::Float)
+                }
                 _ => continue,
             }
             count += 1;
@@ -86,7 +94,99 @@ pub fn update_people(
     })
 }
 
-fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
+/// Validates `update` against the person and the requested profession tab, then
+/// applies it. The trait catalogue is only needed when the update adds a trait.
+pub(crate) fn edit_person(
+    person: &mut Value,
+    profession: &str,
+    update: &PersonUpdate,
+    traits: Option<&TraitCatalogue>,
+) -> AppResult<()> {
+    let actual_profession = resolve_profession(person, profession)?;
+    validate_role_updates(profession, update)?;
+    if let (Some(label), Some(traits)) = (&update.add_trait, traits) {
+        check_trait_conflict(person, label, traits, update)?;
+    }
+
+    apply_updates(person, &actual_profession, update, traits);
+    Ok(())
+}
+
+pub(crate) fn resolve_profession(person: &Value, profession: &str) -> AppResult<String> {
+    let resolved = match profession {
+        "Executive" => get_captain_profession(person),
+        "DepartmentHead" => get_lieutenant_profession(person),
+        _ => Some(profession),
+    };
+    resolved
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Invalid(format!("No {} role found on this person", profession)))
+}
+
+fn validate_role_updates(profession: &str, update: &PersonUpdate) -> AppResult<()> {
+    let bonus_cards = [
+        ("bonusCardMoney", update.bonus_card_money),
+        (
+            "bonusCardInfluencePoints",
+            update.bonus_card_influence_points,
+        ),
+    ];
+    for (field, value) in bonus_cards {
+        let Some(value) = value else { continue };
+        if profession != "DepartmentHead" {
+            return Err(AppError::Invalid(format!(
+                "{} can only be edited on department heads",
+                field
+            )));
+        }
+        if !(0..=MAX_BONUS_CARD_LEVEL).contains(&value) {
+            return Err(AppError::Invalid(format!(
+                "{} must be between 0 and {}",
+                field, MAX_BONUS_CARD_LEVEL
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn check_trait_conflict(
+    person: &Value,
+    label: &str,
+    traits: &TraitCatalogue,
+    update: &PersonUpdate,
+) -> AppResult<()> {
+    if !traits.contains(label) {
+        return Err(AppError::Invalid(format!("Unknown trait {}", label)));
+    }
+
+    let Some(conflict) = traits.conflict_of(label) else {
+        return Ok(());
+    };
+    let has_conflict = person
+        .get("labels")
+        .and_then(|l| l.as_array())
+        .is_some_and(|arr| arr.iter().any(|t| t.as_str() == Some(conflict)));
+    if !has_conflict
+        || update.remove_trait.as_deref() == Some(conflict)
+        || update.resolve_trait_conflicts.unwrap_or(false)
+    {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!(
+            "Trait {} conflicts with existing trait {}",
+            label, conflict
+        )))
+    }
+}
+
+/// Writes `update` onto the person. With `resolveTraitConflicts` set, an added trait
+/// replaces the trait it conflicts with in `traits`.
+pub(crate) fn apply_updates(
+    person: &mut Value,
+    profession: &str,
+    update: &PersonUpdate,
+    traits: Option<&TraitCatalogue>,
+) {
     if let Some(first_name_id) = &update.first_name_id {
         person["firstNameId"] = Value::String(first_name_id.clone());
     }
@@ -107,28 +207,28 @@ fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
         person["studioId"] = studio_id.clone();
     }
     if let Some(mood) = update.mood {
-        person["mood"] = serde_json::json!(mood);
+        set_number(person, "mood", mood, NumberFormat::Float);
     }
     if let Some(attitude) = update.attitude {
-        person["attitude"] = serde_json::json!(attitude);
+        set_number(person, "attitude", attitude, NumberFormat::Float);
     }
     if let Some(self_esteem) = update.self_esteem {
-        person["selfEsteem"] = serde_json::json!(self_esteem);
+        set_number(person, "selfEsteem", self_esteem, NumberFormat::Float);
     }
     if let Some(readiness) = update.readiness {
-        person["readiness"] = serde_json::json!(readiness);
+        set_number(person, "readiness", readiness, NumberFormat::Float);
     }
     if let Some(state) = update.state {
         person["state"] = state.into();
     }
     if let Some(skill) = update.skill {
-        if let Some(profs) = person.get_mut("professions").and_then(|p| p.as_object_mut()) {
-            profs.insert(profession.to_string(), serde_json::json!(skill));
+        if let Some(profs) = person.get_mut("professions").filter(|p| p.is_object()) {
+            set_number(profs, profession, skill, NumberFormat::Float);
         }
     }
     if let Some(limit) = update.limit {
-        person["limit"] = serde_json::json!(limit);
-        person["Limit"] = serde_json::json!(limit);
+        set_number(person, "limit", limit, NumberFormat::Float);
+        set_number(person, "Limit", limit, NumberFormat::Float);
     }
     if let Some(art) = &update.art {
         apply_white_tag_update(person, "ART", art);
@@ -140,6 +240,12 @@ fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
         remove_label(person, label);
     }
     if let Some(label) = &update.add_trait {
+        let conflict = traits
+            .filter(|_| update.resolve_trait_conflicts.unwrap_or(false))
+            .and_then(|t| t.conflict_of(label));
+        if let Some(conflict) = conflict {
+            remove_label(person, conflict);
+        }
         add_label(person, label);
     }
     if let Some(genre) = &update.remove_genre {
@@ -148,6 +254,9 @@ fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
     if let Some(genre) = &update.add_genre {
         upsert_white_tag(person, genre, 12.0);
     }
+    for (genre, value) in update.genre_values.iter().flatten() {
+        upsert_white_tag(person, genre, *value);
+    }
     if let Some(portrait_base_id) = update.portrait_base_id {
         person["portraitBaseId"] = portrait_base_id.into();
     }
@@ -157,6 +266,12 @@ fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
     if let Some(is_shady) = update.is_shady {
         person["isShady"] = serde_json::json!(is_shady);
     }
+    if let Some(salary) = update.contract_monthly_salary {
+        update_contract_amount(person, "monthlySalary", salary);
+    }
+    if let Some(fee) = update.contract_initial_fee {
+        update_contract_amount(person, "initialFee", fee);
+    }
     if let Some(value) = update.bonus_card_money {
         person["BonusCardMoney"] = value.into();
         update_bonus_cards_index(person, 0, value);
@@ -169,7 +284,10 @@ fn apply_updates(person: &mut Value, profession: &str, update: &PersonUpdate) {
 
 fn apply_white_tag_update(person: &mut Value, tag_id: &str, value: &Value) {
     if value.is_null() {
-        if let Some(tags) = person.get_mut("whiteTagsNEW").and_then(|w| w.as_object_mut()) {
+        if let Some(tags) = person
+            .get_mut("whiteTagsNEW")
+            .and_then(|w| w.as_object_mut())
+        {
             tags.remove(tag_id);
         }
     } else if let Some(val) = value.as_f64() {
@@ -177,6 +295,12 @@ fn apply_white_tag_update(person: &mut Value, tag_id: &str, value: &Value) {
     }
 }
 
+fn update_contract_amount(person: &mut Value, field: &str, value: f64) {
+    if let Some(contract) = person.get_mut("contract").filter(|c| c.is_object()) {
+        set_number(contract, field, value, CONTRACT_AMOUNT_FORMAT);
+    }
+}
+
 fn update_birth_year(person: &mut Value, new_year: i32) {
     if let Some(birth_date) = person.get("birthDate").and_then(|d| d.as_str()) {
         let parts: Vec<&str> = birth_date.split('-').collect();
@@ -188,11 +312,14 @@ fn update_birth_year(person: &mut Value, new_year: i32) {
 }
 
 fn update_bonus_cards_index(person: &mut Value, index: usize, value: i32) {
-    if let Some(arr) = person.get_mut("bonusCards").and_then(|b| b.as_array_mut()) {
-        if index < arr.len() {
-            arr[index] = value.into();
-        }
+    if !person.get("bonusCards").is_some_and(|b| b.is_array()) {
+        person["bonusCards"] = serde_json::json!([]);
+    }
+    let arr = person["bonusCards"].as_array_mut().unwrap();
+    if arr.len() <= index {
+        arr.resize(index + 1, 0.into());
     }
+    arr[index] = value.into();
 }
 
 fn add_label(person: &mut Value, label: &str) {
@@ -210,48 +337,3 @@ fn remove_label(person: &mut Value, label: &str) {
         labels.retain(|t| t.as_str() != Some(label));
     }
 }
-
-fn remove_white_tag(person: &mut Value, tag_id: &str) {
-    if let Some(tags) = person.get_mut("whiteTagsNEW").and_then(|w| w.as_object_mut()) {
-        tags.remove(tag_id);
-    }
-}
-
-fn upsert_white_tag(person: &mut Value, tag_id: &str, value: f64) {
-    if person.get("whiteTagsNEW").is_none() {
-        person["whiteTagsNEW"] = serde_json::json!({});
-    }
-    let tags = person["whiteTagsNEW"].as_object_mut().unwrap();
-
-    match tags.get_mut(tag_id) {
-        Some(tag) => {
-            tag["value"] = serde_json::json!(value);
-            if let Some(arr) = tag.get_mut("overallValues").and_then(|o| o.as_array_mut()) {
-                if let Some(entry) = arr.iter_mut().find(|ov| {
-                    ov.get("movieId").and_then(|m| m.as_i64()) == Some(BASE_MOVIE_ID)
-                        && ov.get("sourceType").and_then(|s| s.as_i64()) == Some(BASE_SOURCE_TYPE)
-                }) {
-                    entry["value"] = serde_json::json!(value);
-                }
-            }
-        }
-        None => {
-            tags.insert(
-                tag_id.to_string(),
-                serde_json::json!({
-                    "id": tag_id,
-                    "value": value,
-                    "dateAdded": DEFAULT_DATE_ADDED,
-                    "movieId": BASE_MOVIE_ID,
-                    "IsOverall": false,
-                    "overallValues": [{
-                        "movieId": BASE_MOVIE_ID,
-                        "sourceType": BASE_SOURCE_TYPE,
-                        "value": value,
-                        "dateAdded": DEFAULT_DATE_ADDED
-                    }]
-                }),
-            );
-        }
-    }
-}
@@ -0,0 +1,234 @@
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::person::edit_person;
+use crate::commands::studio::{
+    apply_studio_update, insert_titan, set_resource, set_time_bonus, set_titan, validate_resource,
+    validate_studio_update, validate_time_bonus,
+};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    has_profession, DepartmentCatalogue, Preset, PresetOperation, PresetReport, PresetStepResult,
+    PresetSummary, ResourceCatalogue, TitanCatalogue, TraitCatalogue, PLAYER_STUDIO_ID,
+};
+use crate::state::AppState;
+use crate::utils::paths::PRESETS_FOLDER;
+use crate::utils::{json_id_string, SaveDataExt};
+
+const PRESET_EXTENSIONS: [&str; 2] = ["toml", "json"];
+
+#[tauri::command]
+pub fn list_presets(app: AppHandle) -> AppResult<Vec<PresetSummary>> {
+    let dir = presets_dir(&app)?;
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| preset_extension(path).is_some())
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .iter()
+        .map(|path| {
+            let id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match read_preset(path) {
+                Ok(preset) => PresetSummary {
+                    name: preset.name.unwrap_or_else(|| id.clone()),
+                    description: preset.description,
+                    operation_count: preset.operations.len(),
+                    error: None,
+                    id,
+                },
+                Err(e) => PresetSummary {
+                    name: id.clone(),
+                    description: None,
+                    operation_count: 0,
+                    error: Some(e.to_string()),
+                    id,
+                },
+            }
+        })
+        .collect())
+}
+
+/// Runs every step of the preset against a copy of the save and only commits the
+/// result when all steps succeed and `dry_run` is not set.
+#[tauri::command]
+pub fn apply_preset(
+    id: String,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<PresetReport> {
+    let preset = load_preset(&presets_dir(&app)?, &id)?;
+    let dry_run = dry_run.unwrap_or(false);
+
+    let game_path = state.get_game_path();
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let mut working = Value::clone(&snapshot.data);
+    let steps = preset
+        .operations
+        .iter()
+        .enumerate()
+        .map(|(index, op)| {
+            apply_operation(&mut working, op, game_path.as_deref())
+                .map(|targets| PresetStepResult {
+                    index,
+                    op: op.name().to_string(),
+                    targets,
+                })
+                .map_err(|e| {
+                    AppError::Invalid(format!("Step {} ({}) failed: {}", index + 1, op.name(), e))
+                })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    if !dry_run {
+        state.commit_with_checkpoint(format!("preset {}", id), &snapshot, working)?;
+    }
+
+    Ok(PresetReport { id, dry_run, steps })
+}
+
+fn presets_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::Invalid(format!("Failed to locate app config directory: {}", e)))?
+        .join(PRESETS_FOLDER);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn preset_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    PRESET_EXTENSIONS.iter().find(|e| **e == extension).copied()
+}
+
+fn load_preset(dir: &Path, id: &str) -> AppResult<Preset> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(AppError::Invalid(format!("Invalid preset id {}", id)));
+    }
+    PRESET_EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", id, extension)))
+        .find(|path| path.is_file())
+        .ok_or_else(|| AppError::not_found("Preset", id))
+        .and_then(|path| read_preset(&path))
+}
+
+fn read_preset(path: &Path) -> AppResult<Preset> {
+    let content = fs::read_to_string(path)?;
+    match preset_extension(path) {
+        Some("toml") => toml::from_str(&content)
+            .map_err(|e| AppError::Invalid(format!("Invalid preset: {}", e))),
+        _ => serde_json::from_str(&content)
+            .map_err(|e| AppError::Invalid(format!("Invalid preset: {}", e))),
+    }
+}
+
+/// Applies one preset step and returns the ids of what it changed.
+fn apply_operation(
+    data: &mut Value,
+    op: &PresetOperation,
+    game_path: Option<&str>,
+) -> AppResult<Vec<String>> {
+    match op {
+        PresetOperation::Studio(update) => {
+            validate_studio_update(update, game_path)?;
+            apply_studio_update(data, update)?;
+            Ok(vec![PLAYER_STUDIO_ID.to_string()])
+        }
+        PresetOperation::Resource {
+            resource_id,
+            value,
+            force,
+        } => {
+            let catalogue = ResourceCatalogue::load(game_path);
+            validate_resource(&catalogue, data, resource_id, *value, *force)?;
+            set_resource(data, resource_id, *value)?;
+            Ok(vec![resource_id.clone()])
+        }
+        PresetOperation::Titan {
+            titan_id,
+            value,
+            item1,
+            unlock,
+        } => {
+            let titan_ids = match titan_id {
+                Some(id) => vec![id.clone()],
+                None => TitanCatalogue::load(game_path)
+                    .entries
+                    .into_iter()
+                    .map(|t| t.id)
+                    .collect(),
+            };
+
+            let mut changed = Vec::new();
+            for id in titan_ids {
+                let unlocked = data
+                    .state_json()?
+                    .get("openedTitans")
+                    .is_some_and(|t| t.get(&id).is_some());
+                if unlocked {
+                    set_titan(data, &id, *value, *item1)?;
+                } else if *unlock {
+                    insert_titan(data, &id, item1.unwrap_or(0), *value)?;
+                } else if titan_id.is_some() {
+                    return Err(AppError::Invalid(format!("Titan {} is not unlocked", id)));
+                } else {
+                    continue;
+                }
+                changed.push(id);
+            }
+            Ok(changed)
+        }
+        PresetOperation::TimeBonus { department, value } => {
+            let catalogue = DepartmentCatalogue::load(game_path);
+            let departments = match department {
+                Some(id) => vec![catalogue
+                    .get(id)
+                    .ok_or_else(|| AppError::Invalid(format!("Unknown department {}", id)))?],
+                None => catalogue.entries.iter().collect(),
+            };
+
+            let mut changed = Vec::new();
+            for dept in departments {
+                let value = value.unwrap_or(dept.max_time_bonus);
+                validate_time_bonus(&catalogue, &dept.id, value)?;
+                set_time_bonus(data, &dept.id, value)?;
+                changed.push(dept.id.clone());
+            }
+            Ok(changed)
+        }
+        PresetOperation::Persons {
+            profession,
+            studio_id,
+            update,
+        } => {
+            let traits = update
+                .add_trait
+                .is_some()
+                .then(|| TraitCatalogue::load(game_path));
+
+            let mut changed = Vec::new();
+            for person in data.characters_mut()?.iter_mut() {
+                let in_studio = studio_id.as_deref().map_or(true, |studio| {
+                    person.get("studioId").and_then(|s| s.as_str()) == Some(studio)
+                });
+                if !in_studio || !has_profession(person, profession) {
+                    continue;
+                }
+
+                edit_person(person, profession, update, traits.as_ref())?;
+                changed.push(person.get("id").map(json_id_string).unwrap_or_default());
+            }
+            Ok(changed)
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::commands::transfer::{leave_movies, take_movie_assignments};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    get_leadership_profession, has_profession, in_player_studio, is_known_profession,
+    is_leadership_profession, leadership_replacement, profession_skill, step_down_from_leadership,
+    DemotedHolder, LeadershipChange, ProfessionSkill, PLAYER_STUDIO_ID,
+};
+use crate::state::AppState;
+use crate::utils::{json_id_matches, json_id_string, json_number, SaveDataExt};
+
+const MIN_SKILL: f64 = 0.0;
+const MAX_SKILL: f64 = 1.0;
+
+#[tauri::command]
+pub fn get_professions(
+    person_id: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<ProfessionSkill>> {
+    state.with_save_data(handle.as_deref(), |data| {
+        let person = data.character(&person_id)?;
+        Ok(person
+            .get("professions")
+            .and_then(|p| p.as_object())
+            .map(|map| {
+                map.iter()
+                    .map(|(profession, skill)| ProfessionSkill {
+                        profession: profession.clone(),
+                        skill: json_number(skill).unwrap_or(0.0),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    })
+}
+
+#[tauri::command]
+pub fn add_profession(
+    person_id: String,
+    profession: String,
+    skill: f64,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    if !is_known_profession(&profession) {
+        return Err(AppError::Invalid(format!(
+            "Unknown profession {}",
+            profession
+        )));
+    }
+    validate_skill(skill)?;
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        add_person_profession(
+            data.character_mut(&person_id)?,
+            &person_id,
+            &profession,
+            skill,
+        )
+    })
+}
+
+#[tauri::command]
+pub fn remove_profession(
+    person_id: String,
+    profession: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        remove_person_profession(data.character_mut(&person_id)?, &person_id, &profession)
+    })
+}
+
+/// Moves a player studio character into a captain or lieutenant slot. Leadership
+/// roles are exclusive, so the promoted person gives up their other professions.
+/// A displaced holder swaps into the role the promoted person vacated, or is
+/// released to free agency with `released_profession` when there is none.
+#[tauri::command]
+pub fn assign_leadership_role(
+    person_id: String,
+    role: String,
+    seniority: Option<f64>,
+    released_profession: Option<String>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<LeadershipChange> {
+    if !is_leadership_profession(&role) {
+        return Err(AppError::Invalid(format!(
+            "{} is not an executive or department head role",
+            role
+        )));
+    }
+    if let Some(seniority) = seniority {
+        validate_skill(seniority)?;
+    }
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        assign_role(
+            data,
+            person_id,
+            role,
+            seniority,
+            released_profession.as_deref(),
+        )
+    })
+}
+
+fn add_person_profession(
+    person: &mut Value,
+    person_id: &str,
+    profession: &str,
+    skill: f64,
+) -> AppResult<()> {
+    let empty = serde_json::Map::new();
+    let professions = person
+        .get("professions")
+        .and_then(|p| p.as_object())
+        .unwrap_or(&empty);
+
+    if professions.contains_key(profession) {
+        return Err(AppError::Invalid(format!(
+            "Person {} is already a {}",
+            person_id, profession
+        )));
+    }
+    if let Some(existing) = professions.keys().find(|p| is_leadership_profession(p)) {
+        return Err(AppError::Invalid(format!(
+            "Person {} holds the {} role, which cannot be combined with other professions",
+            person_id, existing
+        )));
+    }
+    if is_leadership_profession(profession) && !professions.is_empty() {
+        return Err(AppError::Invalid(format!(
+            "{} cannot be combined with other professions",
+            profession
+        )));
+    }
+
+    if !person.get("professions").is_some_and(|p| p.is_object()) {
+        person["professions"] = serde_json::json!({});
+    }
+    person["professions"][profession] = serde_json::json!(skill);
+    Ok(())
+}
+
+fn remove_person_profession(
+    person: &mut Value,
+    person_id: &str,
+    profession: &str,
+) -> AppResult<()> {
+    let professions = person
+        .get_mut("professions")
+        .and_then(|p| p.as_object_mut())
+        .ok_or_else(|| AppError::Invalid(format!("Person {} has no professions", person_id)))?;
+
+    if !professions.contains_key(profession) {
+        return Err(AppError::Invalid(format!(
+            "Person {} is not a {}",
+            person_id, profession
+        )));
+    }
+    if professions.len() == 1 {
+        return Err(AppError::Invalid(format!(
+            "Cannot remove {}: it is the only profession of person {}",
+            profession, person_id
+        )));
+    }
+
+    professions.remove(profession);
+    Ok(())
+}
+
+fn assign_role(
+    data: &mut Value,
+    person_id: String,
+    role: String,
+    seniority: Option<f64>,
+    released_profession: Option<&str>,
+) -> AppResult<LeadershipChange> {
+    let characters = data.characters_mut()?;
+    let index = characters
+        .iter()
+        .position(|c| {
+            c.get("id")
+                .is_some_and(|id| json_id_matches(id, &person_id))
+        })
+        .ok_or_else(|| AppError::not_found("Person", &person_id))?;
+
+    let person = &characters[index];
+    if !in_player_studio(person) {
+        return Err(AppError::Invalid(format!(
+            "Person {} must be signed to the player studio to take a leadership role",
+            person_id
+        )));
+    }
+
+    let previous_role = get_leadership_profession(person).map(|r| r.to_string());
+    if previous_role.as_deref() == Some(role.as_str()) {
+        return Err(AppError::Invalid(format!(
+            "Person {} already holds the {} role",
+            person_id, role
+        )));
+    }
+
+    let previous_professions: Vec<String> = person
+        .get("professions")
+        .and_then(|p| p.as_object())
+        .map(|map| map.keys().cloned().collect())
+        .unwrap_or_default();
+    let seniority = seniority
+        .or_else(|| {
+            previous_role
+                .as_deref()
+                .and_then(|r| profession_skill(person, r))
+        })
+        .unwrap_or(MIN_SKILL);
+
+    // The first displaced holder swaps into the slot the promoted person vacated;
+    // any other holder is released and needs a base profession to fall back on.
+    let holders: Vec<usize> = characters
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| *i != index && in_player_studio(c) && has_profession(c, &role))
+        .map(|(i, _)| i)
+        .collect();
+    let swaps = usize::from(previous_role.is_some()).min(holders.len());
+    let released_profession = match holders.get(swaps) {
+        Some(&i) => leadership_replacement(&characters[i], released_profession)?,
+        None => None,
+    };
+
+    let mut demoted: Vec<DemotedHolder> = Vec::new();
+    if let Some(vacated) = &previous_role {
+        for &i in &holders[..swaps] {
+            let holder = &mut characters[i];
+            let holder_skill = profession_skill(holder, &role).unwrap_or(MIN_SKILL);
+            holder["professions"] = serde_json::json!({ vacated.as_str(): holder_skill });
+            demoted.push(DemotedHolder {
+                person_id: holder.get("id").map(json_id_string).unwrap_or_default(),
+                new_role: Some(vacated.clone()),
+                released: false,
+            });
+        }
+    }
+
+    let mut released_movies: Vec<(String, Vec<String>)> = Vec::new();
+    if let Some(base) = released_profession {
+        for &i in &holders[swaps..] {
+            let holder = &mut characters[i];
+            let holder_id = holder.get("id").map(json_id_string).unwrap_or_default();
+            step_down_from_leadership(holder, base);
+            holder["studioId"] = Value::Null;
+            holder["contract"] = Value::Null;
+            released_movies.push((holder_id.clone(), take_movie_assignments(holder)));
+            demoted.push(DemotedHolder {
+                person_id: holder_id,
+                new_role: None,
+                released: true,
+            });
+        }
+    }
+
+    characters[index]["professions"] = serde_json::json!({ role.as_str(): seniority });
+
+    for (holder_id, movie_ids) in &released_movies {
+        leave_movies(data, PLAYER_STUDIO_ID, movie_ids, holder_id)?;
+    }
+
+    Ok(LeadershipChange {
+        person_id,
+        role,
+        previous_professions,
+        demoted,
+    })
+}
+
+fn validate_skill(skill: f64) -> AppResult<()> {
+    if (MIN_SKILL..=MAX_SKILL).contains(&skill) {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!(
+            "Skill {} is out of range ({} to {})",
+            skill, MIN_SKILL, MAX_SKILL
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn save(characters: Value) -> Value {
+        json!({
+            "stateJson": {
+                "characters": characters,
+                "movies": [{ "id": 40, "studioId": "PL", "producerId": 2, "crewIds": [2, 5] }]
+            }
+        })
+    }
+
+    fn person(id: i64, professions: Value) -> Value {
+        json!({
+            "id": id,
+            "studioId": "PL",
+            "professions": professions,
+            "contract": { "amount": 2 },
+            "activeOrPlannedMovies": [40]
+        })
+    }
+
+    fn professions(data: &Value, id: &str) -> Value {
+        data.character(id).unwrap()["professions"].clone()
+    }
+
+    fn assign(data: &mut Value, role: &str, released: Option<&str>) -> AppResult<LeadershipChange> {
+        assign_role(data, "1".to_string(), role.to_string(), None, released)
+    }
+
+    #[test]
+    fn swap_moves_the_holder_into_the_vacated_role() {
+        let mut data = save(json!([
+            person(1, json!({ "LieutScript": 0.4 })),
+            person(2, json!({ "LieutProd": 0.7 }))
+        ]));
+
+        let change = assign(&mut data, "LieutProd", None).unwrap();
+
+        assert_eq!(professions(&data, "1"), json!({ "LieutProd": 0.4 }));
+        assert_eq!(professions(&data, "2"), json!({ "LieutScript": 0.7 }));
+        assert_eq!(change.demoted.len(), 1);
+        assert_eq!(change.demoted[0].new_role.as_deref(), Some("LieutScript"));
+        assert!(!change.demoted[0].released);
+        assert_eq!(data.character("2").unwrap()["studioId"], json!("PL"));
+    }
+
+    #[test]
+    fn promotion_replaces_the_other_professions() {
+        let mut data = save(json!([person(1, json!({ "Actor": 0.6, "Director": 0.3 }))]));
+
+        let change = assign(&mut data, "CptPR", None).unwrap();
+
+        assert_eq!(professions(&data, "1"), json!({ "CptPR": MIN_SKILL }));
+        assert_eq!(change.previous_professions, ["Actor", "Director"]);
+        assert!(change.demoted.is_empty());
+    }
+
+    #[test]
+    fn displaced_holder_without_a_vacated_role_is_released() {
+        let mut data = save(json!([
+            person(1, json!({ "Actor": 0.6 })),
+            person(2, json!({ "CptHR": 0.8 }))
+        ]));
+
+        let change = assign(&mut data, "CptHR", Some("Producer")).unwrap();
+
+        assert_eq!(professions(&data, "1"), json!({ "CptHR": MIN_SKILL }));
+        let holder = data.character("2").unwrap();
+        assert_eq!(holder["professions"], json!({ "Producer": 0.0 }));
+        assert_eq!(holder["studioId"], Value::Null);
+        assert_eq!(holder["contract"], Value::Null);
+        assert_eq!(holder["activeOrPlannedMovies"], json!([]));
+        assert!(change.demoted[0].released);
+
+        let movie = &data["stateJson"]["movies"][0];
+        assert_eq!(movie["producerId"], Value::Null);
+        assert_eq!(movie["crewIds"], json!([5]));
+    }
+
+    #[test]
+    fn release_without_a_base_profession_is_refused() {
+        let mut data = save(json!([
+            person(1, json!({ "Actor": 0.6 })),
+            person(2, json!({ "CptHR": 0.8 }))
+        ]));
+        let before = data.clone();
+
+        assert!(assign(&mut data, "CptHR", None).is_err());
+        assert!(assign(&mut data, "CptHR", Some("CptPR")).is_err());
+        assert_eq!(data, before);
+    }
+
+    #[test]
+    fn leadership_roles_are_not_combined_with_other_professions() {
+        let mut actor = person(1, json!({ "Actor": 0.6 }));
+        assert!(add_person_profession(&mut actor, "1", "CptHR", 0.5).is_err());
+
+        let mut executive = person(2, json!({ "CptHR": 0.6 }));
+        assert!(add_person_profession(&mut executive, "2", "Actor", 0.5).is_err());
+
+        assert_eq!(actor["professions"], json!({ "Actor": 0.6 }));
+        assert_eq!(executive["professions"], json!({ "CptHR": 0.6 }));
+    }
+
+    #[test]
+    fn professions_are_added_once_with_their_skill() {
+        let mut writer = person(1, json!({ "Scriptwriter": 0.5 }));
+        add_person_profession(&mut writer, "1", "Director", 0.3).unwrap();
+        assert_eq!(
+            writer["professions"],
+            json!({ "Scriptwriter": 0.5, "Director": 0.3 })
+        );
+        assert!(add_person_profession(&mut writer, "1", "Director", 0.9).is_err());
+        assert_eq!(writer["professions"]["Director"], json!(0.3));
+
+        let mut blank = json!({ "id": 3 });
+        add_person_profession(&mut blank, "3", "Actor", 0.2).unwrap();
+        assert_eq!(blank["professions"], json!({ "Actor": 0.2 }));
+    }
+
+    #[test]
+    fn the_last_profession_cannot_be_removed() {
+        let mut writer = person(1, json!({ "Scriptwriter": 0.5, "Director": 0.3 }));
+
+        assert!(remove_person_profession(&mut writer, "1", "Actor").is_err());
+        remove_person_profession(&mut writer, "1", "Director").unwrap();
+        assert!(remove_person_profession(&mut writer, "1", "Scriptwriter").is_err());
+        assert_eq!(writer["professions"], json!({ "Scriptwriter": 0.5 }));
+
+        let mut blank = json!({ "id": 3 });
+        assert!(remove_person_profession(&mut blank, "3", "Actor").is_err());
+    }
+
+    #[test]
+    fn skills_must_be_between_zero_and_one() {
+        assert!(validate_skill(MIN_SKILL).is_ok());
+        assert!(validate_skill(MAX_SKILL).is_ok());
+        assert!(validate_skill(-0.1).is_err());
+        assert!(validate_skill(1.1).is_err());
+        assert!(validate_skill(f64::NAN).is_err());
+    }
+}
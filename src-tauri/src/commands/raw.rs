@@ -0,0 +1,67 @@
+use json_patch::Patch;
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::{AppError, AppResult};
+use crate::models::PatchResult;
+use crate::state::AppState;
+use crate::utils::save_validator::validate_save;
+use crate::utils::SaveDataExt;
+
+const PATCH_CHECKPOINT_LABEL: &str = "json patch";
+
+/// Reads the subtree at an RFC 6901 pointer relative to `stateJson`; an empty
+/// pointer returns the whole of `stateJson`.
+#[tauri::command]
+pub fn get_json_pointer(
+    pointer: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Value> {
+    state.with_save_data(handle.as_deref(), |data| {
+        data.state_json()?
+            .pointer(&pointer)
+            .cloned()
+            .ok_or_else(|| AppError::Invalid(format!("Nothing found at {}", pointer)))
+    })
+}
+
+/// Applies RFC 6902 operations to `stateJson`. The patch is refused if it leaves
+/// the save with validation problems it did not have before, unless `force` is set.
+#[tauri::command]
+pub fn apply_json_patch(
+    patch: Patch,
+    force: Option<bool>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<PatchResult> {
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let issues_before = validate_save(&snapshot.data);
+
+    let mut working = Value::clone(&snapshot.data);
+    let state_json = working
+        .get_mut("stateJson")
+        .ok_or_else(|| AppError::missing("stateJson"))?;
+    json_patch::patch(state_json, &patch).map_err(|e| {
+        AppError::Invalid(format!("Patch operation {} failed: {}", e.operation + 1, e))
+    })?;
+
+    let new_issues: Vec<String> = validate_save(&working)
+        .into_iter()
+        .filter(|issue| !issues_before.contains(issue))
+        .collect();
+
+    if !new_issues.is_empty() && !force.unwrap_or(false) {
+        return Err(AppError::Invalid(format!(
+            "Patch rejected, it would leave the save invalid: {}",
+            new_issues.join("; ")
+        )));
+    }
+
+    state.commit_with_checkpoint(PATCH_CHECKPOINT_LABEL.to_string(), &snapshot, working)?;
+    Ok(PatchResult {
+        applied: patch.len(),
+        new_issues,
+        checkpoint: PATCH_CHECKPOINT_LABEL.to_string(),
+    })
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use tauri::State;
+
+use crate::error::AppResult;
+use crate::models::{
+    contract_end_date, contract_field, in_player_studio, primary_profession, white_tag_value,
+    ExpiringContract, PayrollReport, ProfessionPayroll, ScoutSortKey, ScoutedPerson, ScoutingGroup,
+};
+use crate::state::AppState;
+use crate::utils::{age_on, json_id_string, json_number, SaveDataExt};
+
+const UNKNOWN_PROFESSION: &str = "Unknown";
+const UNSIGNED_OWNER: &str = "Unsigned";
+
+#[tauri::command]
+pub fn get_payroll_report(
+    expiring_within_days: i64,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<PayrollReport> {
+    state.with_save_data(handle.as_deref(), |data| {
+        let state_json = data.state_json()?;
+        let today = data.game_date()?;
+
+        let mut by_profession: BTreeMap<String, ProfessionPayroll> = BTreeMap::new();
+        let mut expiring = Vec::new();
+
+        for person in data.characters()?.iter().filter(|c| in_player_studio(c)) {
+            let profession = primary_profession(person).unwrap_or(UNKNOWN_PROFESSION);
+            let monthly_salary = contract_field(person, "monthlySalary");
+
+            let entry = by_profession
+                .entry(profession.to_string())
+                .or_insert_with(|| ProfessionPayroll {
+                    profession: profession.to_string(),
+                    headcount: 0,
+                    monthly_salary: 0.0,
+                    initial_fees: 0.0,
+                });
+            entry.headcount += 1;
+            entry.monthly_salary += monthly_salary;
+            entry.initial_fees += contract_field(person, "initialFee");
+
+            if let Some(end) = contract_end_date(person) {
+                let days_left = (end - today).num_days();
+                if days_left <= expiring_within_days {
+                    expiring.push(ExpiringContract {
+                        person_id: person.get("id").map(json_id_string).unwrap_or_default(),
+                        profession: profession.to_string(),
+                        monthly_salary,
+                        end_date: end.format("%B %d, %Y").to_string(),
+                        days_left,
+                    });
+                }
+            }
+        }
+        expiring.sort_by_key(|c| c.days_left);
+
+        let by_profession: Vec<ProfessionPayroll> = by_profession.into_values().collect();
+        let headcount = by_profession.iter().map(|p| p.headcount).sum();
+        let monthly_salary: f64 = by_profession.iter().map(|p| p.monthly_salary).sum();
+        let initial_fees = by_profession.iter().map(|p| p.initial_fees).sum();
+
+        let budget = state_json
+            .get("budget")
+            .and_then(json_number)
+            .unwrap_or(0.0) as i64;
+        let cash = state_json.get("cash").and_then(json_number).unwrap_or(0.0) as i64;
+
+        Ok(PayrollReport {
+            headcount,
+            monthly_salary,
+            initial_fees,
+            by_profession,
+            expiring,
+            budget,
+            cash,
+            salary_share_of_budget: (budget > 0).then(|| monthly_salary / budget as f64),
+            months_covered_by_cash: (monthly_salary > 0.0).then(|| cash as f64 / monthly_salary),
+        })
+    })
+}
+
+#[tauri::command]
+pub fn scout_talent(
+    sort_by: Option<ScoutSortKey>,
+    max_per_group: Option<usize>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<ScoutingGroup>> {
+    let sort_by = sort_by.unwrap_or_default();
+
+    state.with_save_data(handle.as_deref(), |data| {
+        let today = data.game_date()?;
+
+        let mut groups: BTreeMap<(String, String), Vec<ScoutedPerson>> = BTreeMap::new();
+
+        for person in data.characters()? {
+            if person.get("deathDate").is_some_and(|d| !d.is_null()) {
+                continue;
+            }
+            let Some(professions) = person.get("professions").and_then(|p| p.as_object()) else {
+                continue;
+            };
+
+            let owner = person
+                .get("studioId")
+                .and_then(|s| s.as_str())
+                .filter(|s| !s.is_empty())
+                .unwrap_or(UNSIGNED_OWNER);
+            let limit = person
+                .get("limit")
+                .or_else(|| person.get("Limit"))
+                .and_then(json_number)
+                .unwrap_or(0.0);
+            let tag = |id: &str| {
+                person
+                    .get("whiteTagsNEW")
+                    .and_then(|w| w.get(id))
+                    .map(white_tag_value)
+                    .unwrap_or(0.0)
+            };
+            let age = person
+                .get("birthDate")
+                .and_then(|b| b.as_str())
+                .and_then(|b| age_on(b, today));
+
+            for (profession, skill) in professions {
+                let skill = json_number(skill).unwrap_or(0.0);
+                groups
+                    .entry((profession.clone(), owner.to_string()))
+                    .or_default()
+                    .push(ScoutedPerson {
+                        person_id: person.get("id").map(json_id_string).unwrap_or_default(),
+                        skill,
+                        limit,
+                        headroom: limit - skill,
+                        art: tag("ART"),
+                        com: tag("COM"),
+                        age,
+                    });
+            }
+        }
+
+        Ok(groups
+            .into_iter()
+            .map(|((profession, owner), mut candidates)| {
+                sort_candidates(&mut candidates, sort_by);
+                if let Some(max) = max_per_group {
+                    candidates.truncate(max);
+                }
+                ScoutingGroup {
+                    profession,
+                    owner,
+                    candidates,
+                }
+            })
+            .collect())
+    })
+}
+
+/// Best candidates first: highest stats, or youngest when ranking by age.
+fn sort_candidates(candidates: &mut [ScoutedPerson], sort_by: ScoutSortKey) {
+    match sort_by {
+        ScoutSortKey::Age => candidates.sort_by_key(|c| c.age.unwrap_or(i64::MAX)),
+        _ => candidates.sort_by(|a, b| {
+            let key = |c: &ScoutedPerson| match sort_by {
+                ScoutSortKey::Headroom => c.headroom,
+                ScoutSortKey::Art => c.art,
+                ScoutSortKey::Com => c.com,
+                _ => c.skill,
+            };
+            key(b).total_cmp(&key(a))
+        }),
+    }
+}
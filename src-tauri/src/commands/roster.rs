@@ -0,0 +1,524 @@
+use chrono::{Datelike, NaiveDate};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tauri::State;
+
+use crate::commands::person::{apply_updates, resolve_profession};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    has_profession, profession_skill, white_tag_value, GenreCatalogue, PersonUpdate, RosterRow,
+    RosterRowChange, TraitCatalogue,
+};
+use crate::state::AppState;
+use crate::utils::game_data::load_language_strings;
+use crate::utils::{age_on, json_id_string, json_number, SaveDataExt};
+
+const DEFAULT_LANGUAGE_CODE: &str = "ENG";
+const LIST_SEPARATOR: char = ';';
+const GENRE_VALUE_SEPARATOR: char = ':';
+const VALUE_TOLERANCE: f64 = 1e-4;
+
+const UNIT_RANGE: (f64, f64) = (0.0, 1.0);
+const SELF_ESTEEM_RANGE: (f64, f64) = (-0.99, 2.0);
+
+#[tauri::command]
+pub fn export_roster_csv(
+    profession: String,
+    path: String,
+    language_code: Option<String>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<usize> {
+    let language_code = language_code.unwrap_or_else(|| DEFAULT_LANGUAGE_CODE.to_string());
+    let names = state
+        .get_game_path()
+        .and_then(|game_path| load_language_strings(&game_path, &language_code).ok())
+        .unwrap_or_default();
+    let genres = GenreCatalogue::load(state.get_game_path().as_deref());
+
+    let rows = state.with_save_data(handle.as_deref(), |data| {
+        let today = data.game_date()?;
+        data.characters()?
+            .iter()
+            .filter(|c| has_profession(c, &profession))
+            .map(|person| roster_row(person, &profession, &names, &genres, today))
+            .collect::<AppResult<Vec<_>>>()
+    })?;
+
+    let mut writer = csv::Writer::from_path(&path)?;
+    for row in &rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(rows.len())
+}
+
+#[tauri::command]
+pub fn preview_roster_csv(
+    profession: String,
+    path: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<RosterRowChange>> {
+    let rows = read_roster(&path)?;
+    let traits = TraitCatalogue::load(state.get_game_path().as_deref());
+    let genres = GenreCatalogue::load(state.get_game_path().as_deref());
+
+    state.with_save_data(handle.as_deref(), |data| {
+        Ok(diff_roster(data, &profession, &rows, &traits, &genres))
+    })
+}
+
+#[tauri::command]
+pub fn import_roster_csv(
+    profession: String,
+    path: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<RosterRowChange>> {
+    let rows = read_roster(&path)?;
+    let traits = TraitCatalogue::load(state.get_game_path().as_deref());
+    let genres = GenreCatalogue::load(state.get_game_path().as_deref());
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        let changes = diff_roster(data, &profession, &rows, &traits, &genres);
+        let invalid = changes.iter().filter(|c| c.error.is_some()).count();
+        if invalid > 0 {
+            return Err(AppError::Invalid(format!(
+                "{} row(s) failed validation; nothing was imported",
+                invalid
+            )));
+        }
+
+        let actual_professions = changes
+            .iter()
+            .map(|change| resolve_profession(data.character(&change.person_id)?, &profession))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        for (change, actual_profession) in changes.iter().zip(&actual_professions) {
+            let person = data.character_mut(&change.person_id)?;
+            for update in &change.updates {
+                apply_updates(person, actual_profession, update, None);
+            }
+        }
+        Ok(changes)
+    })
+}
+
+fn read_roster(path: &str) -> AppResult<Vec<RosterRow>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    reader
+        .deserialize()
+        .enumerate()
+        .map(|(i, row)| row.map_err(|e| AppError::Invalid(format!("Row {}: {}", i + 1, e))))
+        .collect()
+}
+
+fn roster_row(
+    person: &Value,
+    profession: &str,
+    names: &[String],
+    genres: &GenreCatalogue,
+    today: NaiveDate,
+) -> AppResult<RosterRow> {
+    let actual_profession = resolve_profession(person, profession)?;
+    let number = |field: &str| person.get(field).and_then(json_number).unwrap_or(0.0);
+    let name = |field: &str| {
+        person
+            .get(field)
+            .and_then(|id| id.as_str())
+            .and_then(|id| id.parse::<usize>().ok())
+            .and_then(|index| names.get(index).cloned())
+            .unwrap_or_default()
+    };
+
+    Ok(RosterRow {
+        id: person.get("id").map(json_id_string).unwrap_or_default(),
+        first_name: name("firstNameId"),
+        last_name: name("lastNameId"),
+        studio: person
+            .get("studioId")
+            .and_then(|s| s.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        age: person
+            .get("birthDate")
+            .and_then(|b| b.as_str())
+            .and_then(|b| age_on(b, today)),
+        skill: profession_skill(person, &actual_profession).unwrap_or(0.0),
+        limit: number("limit"),
+        mood: number("mood"),
+        attitude: number("attitude"),
+        self_esteem: number("selfEsteem"),
+        readiness: number("readiness"),
+        art: white_tag(person, "ART"),
+        com: white_tag(person, "COM"),
+        traits: labels(person).join(&LIST_SEPARATOR.to_string()),
+        genres: person_genres(person, genres)
+            .iter()
+            .map(|(genre, value)| format!("{}{}{}", genre, GENRE_VALUE_SEPARATOR, value))
+            .collect::<Vec<_>>()
+            .join(&LIST_SEPARATOR.to_string()),
+    })
+}
+
+fn diff_roster(
+    data: &Value,
+    profession: &str,
+    rows: &[RosterRow],
+    traits: &TraitCatalogue,
+    genres: &GenreCatalogue,
+) -> Vec<RosterRowChange> {
+    let today = data.game_date();
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let result = match &today {
+                Ok(today) => data
+                    .character(&row.id)
+                    .ok()
+                    .filter(|c| has_profession(c, profession))
+                    .ok_or_else(|| {
+                        AppError::Invalid(format!("No {} with id {}", profession, row.id))
+                    })
+                    .and_then(|person| diff_row(person, profession, row, traits, genres, *today)),
+                Err(e) => Err(AppError::Invalid(e.to_string())),
+            };
+            let (updates, error) = match result {
+                Ok(updates) => (updates, None),
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            RosterRowChange {
+                row: i + 1,
+                person_id: row.id.clone(),
+                updates,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Validates a row and turns its differences from the current character into
+/// updates. Trait removals come before additions so swapping a trait for its
+/// opposite never conflicts.
+fn diff_row(
+    person: &Value,
+    profession: &str,
+    row: &RosterRow,
+    traits: &TraitCatalogue,
+    genres: &GenreCatalogue,
+    today: NaiveDate,
+) -> AppResult<Vec<PersonUpdate>> {
+    check_range("skill", row.skill, UNIT_RANGE)?;
+    check_range("limit", row.limit, UNIT_RANGE)?;
+    check_range("mood", row.mood, UNIT_RANGE)?;
+    check_range("attitude", row.attitude, UNIT_RANGE)?;
+    check_range("self_esteem", row.self_esteem, SELF_ESTEEM_RANGE)?;
+    check_range("readiness", row.readiness, UNIT_RANGE)?;
+    if row.skill > row.limit + VALUE_TOLERANCE {
+        return Err(AppError::Invalid(format!(
+            "skill {} exceeds limit {}",
+            row.skill, row.limit
+        )));
+    }
+
+    let actual_profession = resolve_profession(person, profession)?;
+    let number = |field: &str| person.get(field).and_then(json_number).unwrap_or(0.0);
+    let changed = |current: f64, new: f64| (current - new).abs() > VALUE_TOLERANCE;
+
+    let mut update = PersonUpdate::default();
+    let skill = profession_skill(person, &actual_profession).unwrap_or(0.0);
+    update.skill = changed(skill, row.skill).then_some(row.skill);
+    update.limit = changed(number("limit"), row.limit).then_some(row.limit);
+    update.mood = changed(number("mood"), row.mood).then_some(row.mood);
+    update.attitude = changed(number("attitude"), row.attitude).then_some(row.attitude);
+    update.self_esteem = changed(number("selfEsteem"), row.self_esteem).then_some(row.self_esteem);
+    update.readiness = changed(number("readiness"), row.readiness).then_some(row.readiness);
+    update.art = diff_tag(white_tag(person, "ART"), row.art);
+    update.com = diff_tag(white_tag(person, "COM"), row.com);
+
+    if let (Some(current_age), Some(new_age)) = (
+        person
+            .get("birthDate")
+            .and_then(|b| b.as_str())
+            .and_then(|b| age_on(b, today)),
+        row.age,
+    ) {
+        if current_age != new_age {
+            let birth_year = person
+                .get("birthDate")
+                .and_then(|b| b.as_str())
+                .and_then(|b| NaiveDate::parse_from_str(b, "%d-%m-%Y").ok())
+                .map(|d| d.year())
+                .ok_or_else(|| AppError::Invalid("birthDate is not a valid date".to_string()))?;
+            let shift = i32::try_from(current_age - new_age)
+                .map_err(|e| AppError::Invalid(e.to_string()))?;
+            update.birth_year = Some(birth_year + shift);
+        }
+    }
+
+    let current_genres = person_genres(person, genres);
+    let new_genres = parse_genres(&row.genres, genres)?;
+    let genre_values: BTreeMap<String, f64> = new_genres
+        .iter()
+        .filter(|(genre, value)| {
+            current_genres
+                .get(*genre)
+                .map_or(true, |current| changed(*current, **value))
+        })
+        .map(|(genre, value)| (genre.clone(), *value))
+        .collect();
+    update.genre_values = (!genre_values.is_empty()).then_some(genre_values);
+
+    let mut updates = vec![update];
+    updates.extend(
+        current_genres
+            .keys()
+            .filter(|genre| !new_genres.contains_key(*genre))
+            .map(|genre| PersonUpdate {
+                remove_genre: Some(genre.clone()),
+                ..Default::default()
+            }),
+    );
+
+    let current_traits = labels(person);
+    let new_traits = parse_traits(&row.traits, traits)?;
+    updates.extend(
+        current_traits
+            .iter()
+            .filter(|t| !new_traits.contains(t))
+            .map(|t| PersonUpdate {
+                remove_trait: Some(t.clone()),
+                ..Default::default()
+            }),
+    );
+    updates.extend(
+        new_traits
+            .iter()
+            .filter(|t| !current_traits.contains(t))
+            .map(|t| PersonUpdate {
+                add_trait: Some(t.clone()),
+                ..Default::default()
+            }),
+    );
+
+    updates.retain(|u| !is_empty_update(u));
+    Ok(updates)
+}
+
+fn check_range(field: &str, value: f64, (min, max): (f64, f64)) -> AppResult<()> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(AppError::Invalid(format!(
+            "{} {} is out of range ({} to {})",
+            field, value, min, max
+        )))
+    }
+}
+
+fn diff_tag(current: Option<f64>, new: Option<f64>) -> Option<Value> {
+    match (current, new) {
+        (Some(_), None) => Some(Value::Null),
+        (None, Some(v)) => Some(serde_json::json!(v)),
+        (Some(c), Some(v)) if (c - v).abs() > VALUE_TOLERANCE => Some(serde_json::json!(v)),
+        _ => None,
+    }
+}
+
+/// Checks the fields a roster row can set. `art`/`com` hold `Some(Value::Null)`
+/// when the tag is cleared, which still counts as a change.
+fn is_empty_update(update: &PersonUpdate) -> bool {
+    update.skill.is_none()
+        && update.limit.is_none()
+        && update.mood.is_none()
+        && update.attitude.is_none()
+        && update.self_esteem.is_none()
+        && update.readiness.is_none()
+        && update.art.is_none()
+        && update.com.is_none()
+        && update.birth_year.is_none()
+        && update.genre_values.is_none()
+        && update.remove_genre.is_none()
+        && update.add_trait.is_none()
+        && update.remove_trait.is_none()
+}
+
+fn white_tag(person: &Value, tag_id: &str) -> Option<f64> {
+    person
+        .get("whiteTagsNEW")
+        .and_then(|w| w.get(tag_id))
+        .map(white_tag_value)
+}
+
+fn labels(person: &Value) -> Vec<String> {
+    person
+        .get("labels")
+        .and_then(|l| l.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|t| t.as_str().map(|t| t.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn person_genres(person: &Value, genres: &GenreCatalogue) -> BTreeMap<String, f64> {
+    person
+        .get("whiteTagsNEW")
+        .and_then(|w| w.as_object())
+        .map(|tags| {
+            tags.iter()
+                .filter(|(id, _)| genres.contains(id))
+                .map(|(id, tag)| (id.clone(), white_tag_value(tag)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_traits(cell: &str, traits: &TraitCatalogue) -> AppResult<Vec<String>> {
+    let parsed: Vec<String> = split_list(cell).map(|t| t.to_string()).collect();
+    for label in &parsed {
+        if !traits.contains(label) {
+            return Err(AppError::Invalid(format!("Unknown trait {}", label)));
+        }
+        if let Some(conflict) = traits.conflict_of(label) {
+            if parsed.iter().any(|t| t == conflict) {
+                return Err(AppError::Invalid(format!(
+                    "Trait {} conflicts with {}",
+                    label, conflict
+                )));
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_genres(cell: &str, genres: &GenreCatalogue) -> AppResult<BTreeMap<String, f64>> {
+    split_list(cell)
+        .map(|entry| {
+            let (genre, value) = entry.split_once(GENRE_VALUE_SEPARATOR).ok_or_else(|| {
+                AppError::Invalid(format!("Genre entry {} is missing a value", entry))
+            })?;
+            let genre = genre.trim();
+            if !genres.contains(genre) {
+                return Err(AppError::Invalid(format!("Unknown genre {}", genre)));
+            }
+            let value: f64 = value
+                .trim()
+                .parse()
+                .map_err(|_| AppError::Invalid(format!("Invalid value for genre {}", genre)))?;
+            if value < 0.0 {
+                return Err(AppError::Invalid(format!(
+                    "Genre value {} cannot be negative",
+                    value
+                )));
+            }
+            Ok((genre.to_string(), value))
+        })
+        .collect()
+}
+
+fn split_list(cell: &str) -> impl Iterator<Item = &str> {
+    cell.split(LIST_SEPARATOR)
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn save() -> Value {
+        json!({
+            "stateJson": {
+                "characters": [{
+                    "id": 7,
+                    "studioId": "PL",
+                    "birthDate": "01-06-1900",
+                    "professions": { "Actor": 0.4 },
+                    "limit": 0.8,
+                    "mood": 0.5,
+                    "attitude": 0.5,
+                    "selfEsteem": 1.0,
+                    "readiness": 1.0,
+                    "labels": ["LAZY"],
+                    "whiteTagsNEW": {
+                        "ART": { "value": 2.0 },
+                        "DRAMA": { "value": 6.0 }
+                    }
+                }]
+            }
+        })
+    }
+
+    fn exported_row(data: &Value) -> RosterRow {
+        let person = &data.characters().unwrap()[0];
+        let genres = GenreCatalogue::load(None);
+        roster_row(person, "Actor", &[], &genres, data.game_date().unwrap()).unwrap()
+    }
+
+    fn diff(data: &Value, row: RosterRow) -> RosterRowChange {
+        let traits = TraitCatalogue::load(None);
+        let genres = GenreCatalogue::load(None);
+        diff_roster(data, "Actor", &[row], &traits, &genres).remove(0)
+    }
+
+    #[test]
+    fn unchanged_row_has_no_updates() {
+        let data = save();
+        let change = diff(&data, exported_row(&data));
+
+        assert_eq!(change.error, None);
+        assert!(change.updates.is_empty());
+    }
+
+    #[test]
+    fn changed_cells_become_updates() {
+        let data = save();
+        let mut row = exported_row(&data);
+        row.skill = 0.6;
+        row.art = None;
+        row.traits = "HARDWORKING".to_string();
+        row.genres = "COMEDY:3".to_string();
+
+        let change = diff(&data, row);
+
+        assert_eq!(change.error, None);
+        let [update, remove_genre, remove_trait, add_trait] = &change.updates[..] else {
+            panic!("unexpected updates {:?}", change.updates);
+        };
+        assert_eq!(update.skill, Some(0.6));
+        assert_eq!(update.art, Some(Value::Null));
+        assert_eq!(update.mood, None);
+        assert_eq!(
+            update.genre_values,
+            Some(BTreeMap::from([("COMEDY".to_string(), 3.0)]))
+        );
+        assert_eq!(remove_genre.remove_genre.as_deref(), Some("DRAMA"));
+        assert_eq!(remove_trait.remove_trait.as_deref(), Some("LAZY"));
+        assert_eq!(add_trait.add_trait.as_deref(), Some("HARDWORKING"));
+    }
+
+    #[test]
+    fn invalid_rows_are_reported_without_updates() {
+        let data = save();
+        let invalid: [fn(&mut RosterRow); 6] = [
+            |row| row.id = "99".to_string(),
+            |row| row.skill = 0.9,
+            |row| row.mood = 1.5,
+            |row| row.traits = "LAZY;HARDWORKING".to_string(),
+            |row| row.traits = "FEARLESS".to_string(),
+            |row| row.genres = "DRAMA".to_string(),
+        ];
+
+        for edit in invalid {
+            let mut row = exported_row(&data);
+            edit(&mut row);
+            let change = diff(&data, row);
+            assert!(change.error.is_some());
+            assert!(change.updates.is_empty());
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::AppResult;
+use crate::models::{ScriptOutput, TraitCatalogue};
+use crate::scripting::run_script;
+use crate::state::AppState;
+
+const SCRIPT_CHECKPOINT_LABEL: &str = "script";
+
+/// Runs a Rhai script against a copy of the loaded save. Unless `dry_run` is set,
+/// the result replaces the save and the previous state is kept as an undo checkpoint.
+#[tauri::command]
+pub fn execute_script(
+    source: String,
+    dry_run: Option<bool>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<ScriptOutput> {
+    let dry_run = dry_run.unwrap_or(false);
+    let traits = TraitCatalogue::load(state.get_game_path().as_deref());
+    let snapshot = state.snapshot(handle.as_deref())?;
+    let mut working = Value::clone(&snapshot.data);
+    let run = run_script(&mut working, &source, traits)?;
+
+    let checkpoint = if dry_run {
+        None
+    } else {
+        state.commit_with_checkpoint(SCRIPT_CHECKPOINT_LABEL.to_string(), &snapshot, working)?;
+        Some(SCRIPT_CHECKPOINT_LABEL.to_string())
+    };
+
+    Ok(ScriptOutput {
+        output: run.output,
+        result: run.result,
+        dry_run,
+        checkpoint,
+    })
+}
+
+#[tauri::command]
+pub fn undo_checkpoint(handle: Option<String>, state: State<AppState>) -> AppResult<String> {
+    state.undo_checkpoint(handle.as_deref())
+}
+
+#[tauri::command]
+pub fn list_checkpoints(handle: Option<String>, state: State<AppState>) -> AppResult<Vec<String>> {
+    state.checkpoint_labels(handle.as_deref())
+}
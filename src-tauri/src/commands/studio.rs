@@ -1,13 +1,24 @@
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use tauri::State;
 
-use crate::models::StudioUpdate;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    DepartmentCatalogue, ResourceCatalogue, ResourceInfo, StudioLogoCatalogue, StudioUpdate,
+    TimeBonusStatus, TitanCatalogue, TitanState,
+};
 use crate::state::AppState;
-use crate::utils::{get_state_json_mut, SaveDataExt};
+use crate::utils::{get_state_json_mut, json_number, set_number, NumberFormat, SaveDataExt};
+
+const MAX_STUDIO_NAME_LENGTH: usize = 64;
+const REPUTATION_FORMAT: NumberFormat = NumberFormat::Text { decimals: 3 };
 
 #[tauri::command]
-pub fn get_time_bonuses(state: State<AppState>) -> Result<HashMap<String, i64>, String> {
-    state.with_save_data(|data| {
+pub fn get_time_bonuses(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<HashMap<String, i64>> {
+    state.with_save_data(handle.as_deref(), |data| {
         let state_json = data.state_json()?;
         let bonuses = state_json
             .get("timeBonuses")
@@ -22,64 +33,195 @@ pub fn get_time_bonuses(state: State<AppState>) -> Result<HashMap<String, i64>,
     })
 }
 
+#[tauri::command]
+pub fn get_time_bonus_report(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<TimeBonusStatus>> {
+    let catalogue = DepartmentCatalogue::load(state.get_game_path().as_deref());
+
+    state.with_save_data(handle.as_deref(), |data| {
+        let bonuses = data
+            .state_json()?
+            .get("timeBonuses")
+            .and_then(|t| t.as_object());
+        let entry = |department: &str| bonuses.and_then(|b| b.get(department));
+
+        let mut report: Vec<TimeBonusStatus> = catalogue
+            .entries
+            .iter()
+            .map(|dept| TimeBonusStatus {
+                department: dept.id.clone(),
+                name: dept.name.clone(),
+                value: entry(&dept.id).and_then(json_number).unwrap_or(0.0) as i64,
+                has_entry: entry(&dept.id).is_some(),
+                known: true,
+            })
+            .collect();
+
+        for (department, value) in bonuses.into_iter().flatten() {
+            if department.starts_with('$') || catalogue.get(department).is_some() {
+                continue;
+            }
+            report.push(TimeBonusStatus {
+                department: department.clone(),
+                name: department.clone(),
+                value: json_number(value).unwrap_or(0.0) as i64,
+                has_entry: true,
+                known: false,
+            });
+        }
+        Ok(report)
+    })
+}
+
 #[tauri::command]
 pub fn update_time_bonus(
     department: String,
     value: i64,
+    handle: Option<String>,
     state: State<AppState>,
-) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
-        let state_json = get_state_json_mut(data)?;
+) -> AppResult<()> {
+    let catalogue = DepartmentCatalogue::load(state.get_game_path().as_deref());
+    validate_time_bonus(&catalogue, &department, value)?;
 
-        if state_json.get("timeBonuses").is_none() {
-            state_json["timeBonuses"] = serde_json::json!({});
-        }
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        set_time_bonus(data, &department, value)
+    })
+}
 
-        let bonuses = state_json
-            .get_mut("timeBonuses")
-            .and_then(|t| t.as_object_mut())
-            .ok_or("Failed to access timeBonuses")?;
-
-        if value == 0 {
-            bonuses.remove(&department);
-        } else {
-            bonuses.insert(department, serde_json::json!(value));
-        }
+/// Sets every known department's research bonus to its maximum and returns the new
+/// values.
+#[tauri::command]
+pub fn max_time_bonuses(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<HashMap<String, i64>> {
+    let catalogue = DepartmentCatalogue::load(state.get_game_path().as_deref());
 
-        Ok(())
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        let mut result = HashMap::new();
+        for dept in &catalogue.entries {
+            set_time_bonus(data, &dept.id, dept.max_time_bonus)?;
+            result.insert(dept.id.clone(), dept.max_time_bonus);
+        }
+        Ok(result)
     })
 }
 
+pub(crate) fn validate_time_bonus(
+    catalogue: &DepartmentCatalogue,
+    department: &str,
+    value: i64,
+) -> AppResult<()> {
+    let info = catalogue
+        .get(department)
+        .ok_or_else(|| AppError::Invalid(format!("Unknown department {}", department)))?;
+    if !(0..=info.max_time_bonus).contains(&value) {
+        return Err(AppError::Invalid(format!(
+            "{} research bonus must be between 0 and {} (got {})",
+            info.name, info.max_time_bonus, value
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn set_time_bonus(data: &mut Value, department: &str, value: i64) -> AppResult<()> {
+    time_bonuses_mut(data)?.insert(department.to_string(), serde_json::json!(value));
+    Ok(())
+}
+
+fn time_bonuses_mut(data: &mut Value) -> AppResult<&mut Map<String, Value>> {
+    let state_json = get_state_json_mut(data)?;
+
+    if state_json.get("timeBonuses").is_none() {
+        state_json["timeBonuses"] = serde_json::json!({});
+    }
+
+    state_json
+        .get_mut("timeBonuses")
+        .and_then(|t| t.as_object_mut())
+        .ok_or_else(|| AppError::missing("stateJson.timeBonuses"))
+}
+
 #[tauri::command]
-pub fn update_studio(update: StudioUpdate, state: State<AppState>) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
-        let state_json = get_state_json_mut(data)?;
+pub fn update_studio(
+    update: StudioUpdate,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    validate_studio_update(&update, state.get_game_path().as_deref())?;
 
-        if let Some(budget) = update.budget {
-            state_json["budget"] = serde_json::json!(budget);
-        }
-        if let Some(cash) = update.cash {
-            state_json["cash"] = serde_json::json!(cash);
+    state.with_save_data_mut(handle.as_deref(), |data| apply_studio_update(data, &update))
+}
+
+pub(crate) fn validate_studio_update(
+    update: &StudioUpdate,
+    game_path: Option<&str>,
+) -> AppResult<()> {
+    if let Some(name) = &update.studio_name {
+        if name.trim().is_empty() {
+            return Err(AppError::Invalid("Studio name cannot be empty".to_string()));
         }
-        if let Some(reputation) = update.reputation {
-            state_json["reputation"] = serde_json::json!(format!("{:.3}", reputation));
+        if name.chars().count() > MAX_STUDIO_NAME_LENGTH {
+            return Err(AppError::Invalid(format!(
+                "Studio name cannot be longer than {} characters",
+                MAX_STUDIO_NAME_LENGTH
+            )));
         }
-        if let Some(influence) = update.influence {
-            state_json["influence"] = serde_json::json!(influence);
+    }
+    if let Some(logo_id) = update.studio_logo_id {
+        if !StudioLogoCatalogue::load(game_path).contains(&logo_id) {
+            return Err(AppError::Invalid(format!(
+                "Studio logo {} does not exist",
+                logo_id
+            )));
         }
+    }
+    Ok(())
+}
 
-        Ok(())
-    })
+pub(crate) fn apply_studio_update(data: &mut Value, update: &StudioUpdate) -> AppResult<()> {
+    let state_json = get_state_json_mut(data)?;
+
+    if let Some(budget) = update.budget {
+        set_number(state_json, "budget", budget as f64, NumberFormat::Integer);
+    }
+    if let Some(cash) = update.cash {
+        set_number(state_json, "cash", cash as f64, NumberFormat::Integer);
+    }
+    if let Some(reputation) = update.reputation {
+        set_number(state_json, "reputation", reputation, REPUTATION_FORMAT);
+    }
+    if let Some(influence) = update.influence {
+        set_number(
+            state_json,
+            "influence",
+            influence as f64,
+            NumberFormat::Integer,
+        );
+    }
+    if let Some(name) = &update.studio_name {
+        state_json["studioName"] = serde_json::json!(name.trim());
+    }
+    if let Some(logo_id) = update.studio_logo_id {
+        state_json["studioLogoId"] = serde_json::json!(logo_id);
+    }
+
+    Ok(())
 }
 
 #[tauri::command]
-pub fn get_resources(state: State<AppState>) -> Result<HashMap<String, i64>, String> {
-    state.with_save_data(|data| {
+pub fn get_resources(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<HashMap<String, i64>> {
+    state.with_save_data(handle.as_deref(), |data| {
         let state_json = data.state_json()?;
         let resources = state_json
             .get("otherCountableResources")
             .and_then(|r| r.as_object())
-            .ok_or("Missing otherCountableResources")?;
+            .ok_or_else(|| AppError::missing("stateJson.otherCountableResources"))?;
 
         let mut result = HashMap::new();
         for (key, value) in resources.iter() {
@@ -93,33 +235,108 @@ pub fn get_resources(state: State<AppState>) -> Result<HashMap<String, i64>, Str
     })
 }
 
+#[tauri::command]
+pub fn get_missing_resources(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<ResourceInfo>> {
+    let catalogue = ResourceCatalogue::load(state.get_game_path().as_deref());
+
+    state.with_save_data(handle.as_deref(), |data| {
+        let resources = data
+            .state_json()?
+            .get("otherCountableResources")
+            .and_then(|r| r.as_object())
+            .ok_or_else(|| AppError::missing("stateJson.otherCountableResources"))?;
+
+        Ok(catalogue
+            .entries
+            .into_iter()
+            .filter(|r| !resources.contains_key(&r.id))
+            .collect())
+    })
+}
+
 #[tauri::command]
 pub fn update_resource(
     resource_id: String,
     value: i64,
+    force: Option<bool>,
+    handle: Option<String>,
     state: State<AppState>,
-) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
-        let state_json = get_state_json_mut(data)?;
-
-        let resources = state_json
-            .get_mut("otherCountableResources")
-            .and_then(|r| r.as_object_mut())
-            .ok_or("Missing otherCountableResources")?;
+) -> AppResult<()> {
+    let catalogue = ResourceCatalogue::load(state.get_game_path().as_deref());
 
-        resources.insert(resource_id, serde_json::json!(value));
-        Ok(())
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        validate_resource(
+            &catalogue,
+            data,
+            &resource_id,
+            value,
+            force.unwrap_or(false),
+        )?;
+        set_resource(data, &resource_id, value)
     })
 }
 
+/// Resources the save already holds can always be edited; other keys must be in
+/// the catalogue unless `force` is set.
+pub(crate) fn validate_resource(
+    catalogue: &ResourceCatalogue,
+    data: &Value,
+    resource_id: &str,
+    value: i64,
+    force: bool,
+) -> AppResult<()> {
+    if value < 0 {
+        return Err(AppError::Invalid(format!(
+            "Resource amount {} cannot be negative",
+            value
+        )));
+    }
+    let in_save = data
+        .state_json()?
+        .get("otherCountableResources")
+        .is_some_and(|r| r.get(resource_id).is_some());
+    if force {
+        return Ok(());
+    }
+    match catalogue.get(resource_id) {
+        None if !in_save => Err(AppError::Invalid(format!(
+            "Unknown resource {}",
+            resource_id
+        ))),
+        Some(resource) if value > resource.max => Err(AppError::Invalid(format!(
+            "{} cannot be set above {}",
+            resource.name, resource.max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn set_resource(data: &mut Value, resource_id: &str, value: i64) -> AppResult<()> {
+    let state_json = get_state_json_mut(data)?;
+
+    let resources = state_json
+        .get_mut("otherCountableResources")
+        .and_then(|r| r.as_object_mut())
+        .ok_or_else(|| AppError::missing("stateJson.otherCountableResources"))?;
+
+    resources.insert(resource_id.to_string(), serde_json::json!(value));
+    Ok(())
+}
+
 #[tauri::command]
-pub fn get_titans(state: State<AppState>) -> Result<HashMap<String, i64>, String> {
-    state.with_save_data(|data| {
+pub fn get_titans(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<HashMap<String, i64>> {
+    state.with_save_data(handle.as_deref(), |data| {
         let state_json = data.state_json()?;
         let opened = state_json
             .get("openedTitans")
             .and_then(|t| t.as_object())
-            .ok_or("Missing openedTitans")?;
+            .ok_or_else(|| AppError::missing("stateJson.openedTitans"))?;
 
         let mut result = HashMap::new();
         for (key, value) in opened.iter() {
@@ -132,20 +349,187 @@ pub fn get_titans(state: State<AppState>) -> Result<HashMap<String, i64>, String
 }
 
 #[tauri::command]
-pub fn update_titan(titan_id: String, value: i64, state: State<AppState>) -> Result<(), String> {
-    state.with_save_data_mut(|data| {
-        let state_json = get_state_json_mut(data)?;
+pub fn get_titan_states(
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<TitanState>> {
+    let catalogue = TitanCatalogue::load(state.get_game_path().as_deref());
 
-        let opened = state_json
-            .get_mut("openedTitans")
-            .and_then(|t| t.as_object_mut())
-            .ok_or("Missing openedTitans")?;
-
-        if let Some(titan) = opened.get_mut(&titan_id) {
-            titan["Item2"] = serde_json::json!(value);
-            Ok(())
-        } else {
-            Err(format!("Titan {} not found in openedTitans", titan_id))
+    state.with_save_data(handle.as_deref(), |data| {
+        let opened = data
+            .state_json()?
+            .get("openedTitans")
+            .and_then(|t| t.as_object())
+            .ok_or_else(|| AppError::missing("stateJson.openedTitans"))?;
+
+        let mut titans: Vec<TitanState> = catalogue
+            .entries
+            .into_iter()
+            .map(|info| {
+                let entry = opened.get(&info.id);
+                TitanState {
+                    unlocked: entry.is_some(),
+                    item1: entry
+                        .and_then(|e| e.get("Item1"))
+                        .and_then(json_number)
+                        .map(|v| v as i64),
+                    item2: entry
+                        .and_then(|e| e.get("Item2"))
+                        .and_then(json_number)
+                        .map(|v| v as i64),
+                    id: info.id,
+                    faction: Some(info.faction),
+                }
+            })
+            .collect();
+
+        // Titans present in the save but unknown to the catalogue are still listed so
+        // that they can be edited or locked.
+        for (key, entry) in opened.iter() {
+            if key.starts_with('$') || titans.iter().any(|t| &t.id == key) {
+                continue;
+            }
+            titans.push(TitanState {
+                id: key.clone(),
+                faction: None,
+                unlocked: true,
+                item1: entry.get("Item1").and_then(json_number).map(|v| v as i64),
+                item2: entry.get("Item2").and_then(json_number).map(|v| v as i64),
+            });
+        }
+        Ok(titans)
+    })
+}
+
+#[tauri::command]
+pub fn update_titan(
+    titan_id: String,
+    value: i64,
+    item1: Option<i64>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        set_titan(data, &titan_id, value, item1)
+    })
+}
+
+#[tauri::command]
+pub fn unlock_titan(
+    titan_id: String,
+    item1: Option<i64>,
+    item2: Option<i64>,
+    force: Option<bool>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    if !force.unwrap_or(false) {
+        let catalogue = TitanCatalogue::load(state.get_game_path().as_deref());
+        if catalogue.get(&titan_id).is_none() {
+            return Err(AppError::Invalid(format!("Unknown titan {}", titan_id)));
         }
+    }
+
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        insert_titan(data, &titan_id, item1.unwrap_or(0), item2.unwrap_or(0))
     })
 }
+
+#[tauri::command]
+pub fn lock_titan(
+    titan_id: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        opened_titans_mut(data)?
+            .remove(&titan_id)
+            .map(|_| ())
+            .ok_or_else(|| AppError::not_found("Titan", &titan_id))
+    })
+}
+
+pub(crate) fn set_titan(
+    data: &mut Value,
+    titan_id: &str,
+    value: i64,
+    item1: Option<i64>,
+) -> AppResult<()> {
+    let titan = opened_titans_mut(data)?
+        .get_mut(titan_id)
+        .ok_or_else(|| AppError::not_found("Titan", titan_id))?;
+
+    if let Some(item1) = item1 {
+        set_number(titan, "Item1", item1 as f64, NumberFormat::Integer);
+    }
+    set_number(titan, "Item2", value as f64, NumberFormat::Integer);
+    Ok(())
+}
+
+pub(crate) fn insert_titan(
+    data: &mut Value,
+    titan_id: &str,
+    item1: i64,
+    item2: i64,
+) -> AppResult<()> {
+    let opened = opened_titans_mut(data)?;
+    if opened.contains_key(titan_id) {
+        return Err(AppError::Invalid(format!(
+            "Titan {} is already unlocked",
+            titan_id
+        )));
+    }
+
+    // Reuse an existing entry as the template so the tuple keeps whatever `$type`
+    // annotation the game serialized.
+    let mut entry = opened
+        .iter()
+        .find(|(key, value)| !key.starts_with('$') && value.is_object())
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(|| serde_json::json!({ "Item1": 0, "Item2": 0 }));
+    set_number(&mut entry, "Item1", item1 as f64, NumberFormat::Integer);
+    set_number(&mut entry, "Item2", item2 as f64, NumberFormat::Integer);
+
+    opened.insert(titan_id.to_string(), entry);
+    Ok(())
+}
+
+fn opened_titans_mut(data: &mut Value) -> AppResult<&mut Map<String, Value>> {
+    get_state_json_mut(data)?
+        .get_mut("openedTitans")
+        .and_then(|t| t.as_object_mut())
+        .ok_or_else(|| AppError::missing("stateJson.openedTitans"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn save() -> Value {
+        json!({ "stateJson": { "otherCountableResources": { "ALCOHOL": 1, "MODDED_ITEM": 3 } } })
+    }
+
+    #[test]
+    fn resources_are_capped_at_their_maximum_unless_forced() {
+        let catalogue = ResourceCatalogue::load(None);
+        let max = catalogue.get("EUROPEAN_SPORTCAR").unwrap().max;
+        let accepts = |value, force| {
+            validate_resource(&catalogue, &save(), "EUROPEAN_SPORTCAR", value, force).is_ok()
+        };
+
+        assert!(accepts(max, false));
+        assert!(!accepts(max + 1, false));
+        assert!(accepts(max + 1, true));
+        assert!(!accepts(-1, true));
+    }
+
+    #[test]
+    fn unknown_resources_need_force_unless_already_in_the_save() {
+        let catalogue = ResourceCatalogue::load(None);
+
+        assert!(validate_resource(&catalogue, &save(), "MODDED_ITEM", 50_000, false).is_ok());
+        assert!(validate_resource(&catalogue, &save(), "NEW_ITEM", 1, false).is_err());
+        assert!(validate_resource(&catalogue, &save(), "NEW_ITEM", 1, true).is_ok());
+    }
+}
@@ -0,0 +1,262 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    leadership_replacement, step_down_from_leadership, TransferResult, PLAYER_STUDIO_ID,
+};
+use crate::state::AppState;
+use crate::utils::{get_state_json_mut, json_id_matches, json_id_string, SaveDataExt};
+
+const DEFAULT_CONTRACT_YEARS: i64 = 1;
+
+/// Movie fields holding the id of a single staff member.
+const MOVIE_STAFF_FIELDS: [&str; 6] = [
+    "directorId",
+    "producerId",
+    "scriptwriterId",
+    "filmEditorId",
+    "composerId",
+    "cinematographerId",
+];
+
+/// Movie fields listing the ids of the cast and crew.
+const MOVIE_STAFF_LISTS: [&str; 2] = ["actorIds", "crewIds"];
+
+/// Moves a person to another studio or to free agency. Leadership roles belong to
+/// the player studio, so a captain or lieutenant takes `base_profession` instead.
+#[tauri::command]
+pub fn transfer_person(
+    person_id: String,
+    studio_id: Option<String>,
+    base_profession: Option<String>,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<TransferResult> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        transfer(data, person_id, studio_id, base_profession.as_deref())
+    })
+}
+
+fn transfer(
+    data: &mut Value,
+    person_id: String,
+    studio_id: Option<String>,
+    base_profession: Option<&str>,
+) -> AppResult<TransferResult> {
+    let state_json = data.state_json()?;
+    if let Some(target) = studio_id.as_deref() {
+        validate_target_studio(state_json, target)?;
+    }
+    let signing_date = data.game_date()?.format("%Y-%m-%dT00:00:00").to_string();
+
+    let person = data.character_mut(&person_id)?;
+    let from = person
+        .get("studioId")
+        .and_then(|s| s.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    if from == studio_id {
+        return Err(AppError::Invalid(format!(
+            "Person {} is already with that studio",
+            person_id
+        )));
+    }
+    let base_profession = leadership_replacement(person, base_profession)?;
+
+    let movie_ids = take_movie_assignments(person);
+    if let Some(base) = base_profession {
+        step_down_from_leadership(person, base);
+    }
+
+    let (contract_created, contract_cleared) = match &studio_id {
+        Some(target) => {
+            person["studioId"] = Value::String(target.clone());
+            person["contract"] = new_contract(person.get("contract"), &signing_date);
+            (true, false)
+        }
+        None => {
+            person["studioId"] = Value::Null;
+            let had_contract = person.get("contract").is_some_and(|c| !c.is_null());
+            person["contract"] = Value::Null;
+            (false, had_contract)
+        }
+    };
+
+    let movies_left = match &from {
+        Some(old_studio) => leave_movies(data, old_studio, &movie_ids, &person_id)?,
+        None => Vec::new(),
+    };
+
+    Ok(TransferResult {
+        person_id,
+        from,
+        to: studio_id,
+        contract_created,
+        contract_cleared,
+        movies_left,
+    })
+}
+
+fn validate_target_studio(state_json: &Value, target: &str) -> AppResult<()> {
+    if target == PLAYER_STUDIO_ID {
+        return Ok(());
+    }
+    let competitor = state_json
+        .get("competitorStudios")
+        .and_then(|c| c.get(target))
+        .ok_or_else(|| AppError::not_found("Studio", target))?;
+    if competitor
+        .get("isDead")
+        .and_then(|d| d.as_bool())
+        .unwrap_or(false)
+    {
+        return Err(AppError::Invalid(format!(
+            "Studio {} is no longer in business",
+            target
+        )));
+    }
+    Ok(())
+}
+
+/// Builds the contract for a newly signed person, keeping the salary terms of their
+/// previous contract when there is one.
+fn new_contract(previous: Option<&Value>, signing_date: &str) -> Value {
+    let mut contract = previous
+        .filter(|c| c.is_object())
+        .cloned()
+        .unwrap_or_else(|| {
+            serde_json::json!({
+                "contractType": 0,
+                "amount": DEFAULT_CONTRACT_YEARS,
+                "startAmount": DEFAULT_CONTRACT_YEARS,
+                "initialFee": "0",
+                "monthlySalary": "0",
+                "weightToSalary": "1"
+            })
+        });
+    contract["dateOfSigning"] = Value::String(signing_date.to_string());
+    contract
+}
+
+/// Clears the person's movie assignments and returns the ids of those movies.
+pub(crate) fn take_movie_assignments(person: &mut Value) -> Vec<String> {
+    let movie_ids = person
+        .get("activeOrPlannedMovies")
+        .and_then(|m| m.as_array())
+        .map(|arr| arr.iter().filter_map(movie_reference_id).collect())
+        .unwrap_or_default();
+    person["activeOrPlannedMovies"] = serde_json::json!([]);
+    movie_ids
+}
+
+fn movie_reference_id(reference: &Value) -> Option<String> {
+    match reference {
+        Value::Number(_) | Value::String(_) => Some(json_id_string(reference)),
+        Value::Object(_) => reference.get("id").map(json_id_string),
+        _ => None,
+    }
+}
+
+/// Drops the person from the old studio's movies they were attached to: staff
+/// fields pointing at them are cleared and their entries are removed from the
+/// cast and crew lists. Returns the ids of the movies that were changed.
+pub(crate) fn leave_movies(
+    data: &mut Value,
+    old_studio: &str,
+    movie_ids: &[String],
+    person_id: &str,
+) -> AppResult<Vec<String>> {
+    let Some(movies) = get_state_json_mut(data)?
+        .get_mut("movies")
+        .and_then(|m| m.as_array_mut())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut changed = Vec::new();
+    for movie in movies.iter_mut() {
+        let Some(movie_id) = movie.get("id").map(json_id_string) else {
+            continue;
+        };
+        let owned_by_old_studio =
+            movie.get("studioId").and_then(|s| s.as_str()) == Some(old_studio);
+        if !movie_ids.contains(&movie_id) || !owned_by_old_studio {
+            continue;
+        }
+
+        let mut touched = false;
+        for key in MOVIE_STAFF_FIELDS {
+            if let Some(field) = movie.get_mut(key) {
+                if json_id_matches(field, person_id) {
+                    *field = Value::Null;
+                    touched = true;
+                }
+            }
+        }
+        for key in MOVIE_STAFF_LISTS {
+            if let Some(entries) = movie.get_mut(key).and_then(|e| e.as_array_mut()) {
+                let before = entries.len();
+                entries.retain(|e| !json_id_matches(e, person_id));
+                touched |= entries.len() != before;
+            }
+        }
+        if touched {
+            changed.push(movie_id);
+        }
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn save(professions: Value) -> Value {
+        json!({
+            "stateJson": {
+                "competitorStudios": { "EM": { "isDead": false } },
+                "characters": [{
+                    "id": 1,
+                    "studioId": "PL",
+                    "professions": professions,
+                    "contract": { "amount": 2, "monthlySalary": "250" },
+                    "activeOrPlannedMovies": [40]
+                }],
+                "movies": [{ "id": 40, "studioId": "PL", "directorId": 1, "actorIds": [1, 2] }]
+            }
+        })
+    }
+
+    #[test]
+    fn transfer_leaves_the_old_studio_movies() {
+        let mut data = save(json!({ "Director": 0.5 }));
+
+        let result = transfer(&mut data, "1".to_string(), Some("EM".to_string()), None).unwrap();
+
+        let person = data.character("1").unwrap();
+        assert_eq!(person["studioId"], json!("EM"));
+        assert_eq!(person["contract"]["monthlySalary"], json!("250"));
+        assert_eq!(person["activeOrPlannedMovies"], json!([]));
+        assert_eq!(result.movies_left, ["40"]);
+        let movie = &data["stateJson"]["movies"][0];
+        assert_eq!(movie["directorId"], Value::Null);
+        assert_eq!(movie["actorIds"], json!([2]));
+    }
+
+    #[test]
+    fn leadership_holder_needs_a_base_profession() {
+        let mut data = save(json!({ "CptHR": 0.5 }));
+        let before = data.clone();
+
+        assert!(transfer(&mut data, "1".to_string(), None, None).is_err());
+        assert_eq!(data, before);
+
+        let result = transfer(&mut data, "1".to_string(), None, Some("Producer")).unwrap();
+        let person = data.character("1").unwrap();
+        assert_eq!(person["professions"], json!({ "Producer": 0.0 }));
+        assert_eq!(person["studioId"], Value::Null);
+        assert!(result.contract_cleared);
+    }
+}
@@ -0,0 +1,78 @@
+use tauri::State;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    remove_white_tag, set_overall_value, upsert_white_tag, validate_overall_edits, white_tag_info,
+    WhiteTagInfo, WhiteTagUpdate,
+};
+use crate::state::AppState;
+use crate::utils::SaveDataExt;
+
+#[tauri::command]
+pub fn get_white_tags(
+    person_id: String,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<Vec<WhiteTagInfo>> {
+    state.with_save_data(handle.as_deref(), |data| {
+        let person = data.character(&person_id)?;
+        Ok(person
+            .get("whiteTagsNEW")
+            .and_then(|w| w.as_object())
+            .map(|tags| {
+                tags.iter()
+                    .map(|(id, tag)| white_tag_info(id, tag))
+                    .collect()
+            })
+            .unwrap_or_default())
+    })
+}
+
+#[tauri::command]
+pub fn set_white_tag(
+    person_id: String,
+    tag_id: String,
+    update: WhiteTagUpdate,
+    handle: Option<String>,
+    state: State<AppState>,
+) -> AppResult<()> {
+    state.with_save_data_mut(handle.as_deref(), |data| {
+        let person = data.character_mut(&person_id)?;
+
+        if update.remove.unwrap_or(false) {
+            remove_white_tag(person, &tag_id);
+            return Ok(());
+        }
+
+        let existing = person.get("whiteTagsNEW").and_then(|w| w.get(&tag_id));
+        if existing.is_none() && update.value.is_none() {
+            return Err(AppError::not_found("Tag", &tag_id));
+        }
+        let entries = update.entries.as_deref().unwrap_or_default();
+        validate_overall_edits(existing, &tag_id, entries)?;
+
+        if let Some(value) = update.value {
+            upsert_white_tag(person, &tag_id, value);
+        }
+
+        let tag = person
+            .get_mut("whiteTagsNEW")
+            .and_then(|w| w.get_mut(&tag_id))
+            .ok_or_else(|| AppError::not_found("Tag", &tag_id))?;
+        if let Some(is_overall) = update.is_overall {
+            tag["IsOverall"] = serde_json::json!(is_overall);
+        }
+
+        for entry in entries {
+            set_overall_value(
+                person,
+                &tag_id,
+                entry.movie_id,
+                entry.source_type,
+                entry.value,
+            )?;
+        }
+
+        Ok(())
+    })
+}
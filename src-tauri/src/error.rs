@@ -0,0 +1,154 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::error::Category;
+use serde_json::{json, Value};
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Errors returned by commands. They serialize as `{ code, message, details }` so
+/// the frontend can branch on `code` instead of matching message text.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("No save file loaded")]
+    NoSaveLoaded,
+    #[error("Game installation not found. Please click 'Browse for Game Folder' and select your Hollywood Animal installation directory.")]
+    GameNotFound,
+    #[error("Missing {path} in save data")]
+    MissingField { path: String },
+    #[error("{kind} {id} not found")]
+    NotFound { kind: String, id: String },
+    #[error("Parse error at line {line}, column {col}: {message}")]
+    Parse {
+        line: usize,
+        col: usize,
+        message: String,
+    },
+    #[error("{path} was changed on disk since it was loaded")]
+    ModifiedOnDisk { path: String },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A rejected edit or any other failure without a more specific variant.
+    #[error("{0}")]
+    Invalid(String),
+}
+
+impl AppError {
+    pub fn missing(path: impl Into<String>) -> Self {
+        Self::MissingField { path: path.into() }
+    }
+
+    pub fn not_found(kind: impl Into<String>, id: impl Into<String>) -> Self {
+        Self::NotFound {
+            kind: kind.into(),
+            id: id.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoSaveLoaded => "NO_SAVE_LOADED",
+            Self::GameNotFound => "GAME_NOT_FOUND",
+            Self::MissingField { .. } => "MISSING_FIELD",
+            Self::NotFound { .. } => "NOT_FOUND",
+            Self::Parse { .. } => "PARSE",
+            Self::ModifiedOnDisk { .. } => "MODIFIED_ON_DISK",
+            Self::Io(_) => "IO",
+            Self::Invalid(_) => "INVALID",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            Self::MissingField { path } => json!({ "path": path }),
+            Self::NotFound { kind, id } => json!({ "kind": kind, "id": id }),
+            Self::Parse { line, col, .. } => json!({ "line": line, "col": col }),
+            Self::ModifiedOnDisk { path } => json!({ "path": path }),
+            Self::Io(e) => json!({ "kind": e.kind().to_string() }),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Only errors with a position in the input become `Parse`. Reading failures keep
+/// their I/O error, truncated input is reported as such, and errors from
+/// converting an in-memory `Value` (which have no position) become `Invalid`.
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        match e.classify() {
+            Category::Io => Self::Io(e.into()),
+            Category::Eof => Self::Invalid(format!(
+                "JSON ended unexpectedly; the file may be truncated ({})",
+                e
+            )),
+            Category::Data if e.line() == 0 => Self::Invalid(e.to_string()),
+            Category::Syntax | Category::Data => Self::Parse {
+                line: e.line(),
+                col: e.column(),
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<csv::Error> for AppError {
+    fn from(e: csv::Error) -> Self {
+        let message = e.to_string();
+        match e.into_kind() {
+            csv::ErrorKind::Io(e) => Self::Io(e),
+            _ => Self::Invalid(message),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("details", &self.details())?;
+        error.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Read};
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+        }
+    }
+
+    #[test]
+    fn syntax_errors_keep_their_position() {
+        let err: AppError = serde_json::from_str::<Value>("{\n  \"a\": ]")
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, AppError::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn read_failures_are_io_errors() {
+        let err: AppError = serde_json::from_reader::<_, Value>(FailingReader)
+            .unwrap_err()
+            .into();
+        match err {
+            AppError::Io(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied),
+            other => panic!("expected Io, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_input_and_value_conversions_are_not_parse_errors() {
+        let truncated: AppError = serde_json::from_str::<Value>("{\"a\": [1,")
+            .unwrap_err()
+            .into();
+        assert_eq!(truncated.code(), "INVALID");
+
+        let conversion: AppError = serde_json::from_value::<u8>(json!("x")).unwrap_err().into();
+        assert_eq!(conversion.code(), "INVALID");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod commands;
+pub mod error;
 pub mod models;
+pub mod scripting;
 pub mod state;
 pub mod utils;
 
@@ -3,8 +3,11 @@
     windows_subsystem = "windows"
 )]
 
+mod cli;
 mod commands;
+mod error;
 mod models;
+mod scripting;
 mod state;
 mod utils;
 
@@ -12,29 +15,80 @@ use commands::*;
 use state::AppState;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_script_invocation(&args) {
+        cli::attach_parent_console();
+        if let Err(e) = cli::run(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::default())
         .invoke_handler(tauri::generate_handler![
             load_save_file,
+            get_save_info,
             save_save_file,
+            close_save_file,
+            list_open_saves,
+            merge_with_disk,
             get_persons,
             update_person,
             update_people,
+            get_professions,
+            add_profession,
+            remove_profession,
+            assign_leadership_role,
+            transfer_person,
+            export_persons,
+            import_persons,
+            copy_person_between_saves,
+            export_roster_csv,
+            preview_roster_csv,
+            import_roster_csv,
             update_studio,
             get_current_date,
             get_language_strings,
             get_game_path,
             set_game_path,
+            get_trait_catalogue,
+            get_studio_logos,
+            get_genre_catalogue,
+            get_person_genres,
+            set_genre_value,
+            set_genre_entry,
+            get_white_tags,
+            set_white_tag,
             get_resources,
             update_resource,
+            get_missing_resources,
+            get_resource_catalogue,
             get_titans,
             update_titan,
+            get_titan_states,
+            unlock_titan,
+            lock_titan,
+            get_titan_catalogue,
             get_competitors,
             update_competitor,
             get_time_bonuses,
             update_time_bonus,
+            get_time_bonus_report,
+            max_time_bonuses,
+            get_department_catalogue,
+            get_payroll_report,
+            scout_talent,
+            list_presets,
+            apply_preset,
+            execute_script,
+            undo_checkpoint,
+            list_checkpoints,
+            get_json_pointer,
+            apply_json_patch,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const PERSON_BUNDLE_VERSION: u32 = 1;
+
+/// A standalone export of characters that can be imported into another save.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonBundle {
+    pub version: u32,
+    pub language_code: String,
+    pub persons: Vec<BundledPerson>,
+}
+
+/// A character with its names resolved to text, since name ids only have meaning
+/// against a particular localization file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundledPerson {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub character: Value,
+}
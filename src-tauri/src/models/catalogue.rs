@@ -0,0 +1,558 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::game_data::{config_entries, config_entry_id, load_config_file};
+use crate::utils::paths;
+
+const BUILTIN_STUDIO_LOGO_COUNT: i64 = 13;
+const STUDIO_LOGO_FILE_PREFIX: &str = "PL";
+
+const TRAITS_CONFIG_FILE: &str = "Labels.json";
+const TRAITS_CONFIG_KEY: &str = "labels";
+
+const TITANS_CONFIG_FILE: &str = "Titans.json";
+const TITANS_CONFIG_KEY: &str = "titans";
+
+const DEPARTMENTS_CONFIG_FILE: &str = "Departments.json";
+const DEPARTMENTS_CONFIG_KEY: &str = "departments";
+
+const BUILTIN_TRAITS: [&str; 26] = [
+    "ALCOHOLIC",
+    "ARROGANT",
+    "CALM",
+    "CHASTE",
+    "CHEERY",
+    "DEMANDING",
+    "DISCIPLINED",
+    "HARDWORKING",
+    "HEARTBREAKER",
+    "HOTHEADED",
+    "INDIFFERENT",
+    "JUNKIE",
+    "LAZY",
+    "LEADER",
+    "LUDOMANIAC",
+    "MELANCHOLIC",
+    "MISOGYNIST",
+    "MODEST",
+    "OPEN_MINDED",
+    "PERFECTIONIST",
+    "RACIST",
+    "SIMPLE",
+    "TEAM_PLAYER",
+    "UNDISCIPLINED",
+    "UNWANTED_ACTOR",
+    "XENOPHOBE",
+];
+
+const BUILTIN_TRAIT_CONFLICTS: [(&str, &str); 8] = [
+    ("HARDWORKING", "LAZY"),
+    ("DISCIPLINED", "UNDISCIPLINED"),
+    ("PERFECTIONIST", "INDIFFERENT"),
+    ("HOTHEADED", "CALM"),
+    ("DEMANDING", "MODEST"),
+    ("ARROGANT", "SIMPLE"),
+    ("HEARTBREAKER", "CHASTE"),
+    ("CHEERY", "MELANCHOLIC"),
+];
+
+const BUILTIN_GENRES: [&str; 10] = [
+    "ACTION",
+    "DRAMA",
+    "HISTORICAL",
+    "THRILLER",
+    "ROMANCE",
+    "DETECTIVE",
+    "COMEDY",
+    "ADVENTURE",
+    "HORROR",
+    "SCIENCE_FICTION",
+];
+
+/// Resource id, display name and the most the editor sets without `force`.
+const BUILTIN_RESOURCES: [(&str, &str, i64); 14] = [
+    ("ALCOHOL", "Whiskey Glennafola 50 Cask Strength", 999),
+    ("EUROPEAN_SPORTCAR", "Lussuria Atlantic Sports Car", 20),
+    ("SIGARS", "Alexandre Dumas Siglo VI Cigars", 999),
+    ("WARDROBE_COUTURE", "Wardrobe by Christo Duvalier", 99),
+    ("WATCH", "Silvermoon Kronos Watch", 99),
+    ("ANIMAL_MURDER", "Safari", 20),
+    ("CANNIBAL_DINNER", "Human Flesh", 99),
+    ("COCAINE", "Cocaine", 999),
+    ("EVENING_WITH_UNDERAGED", "Time with a Minor", 20),
+    ("HEROIN", "Heroin", 999),
+    ("ILLEGAL_SAFARI", "Pilgrim-Style Hunt", 20),
+    ("METH", "Methamphetamines", 999),
+    ("MONKEY_BRAINS", "Monkey Brains", 99),
+    ("PORNO_TAPE", "A Spicy Film Strip", 99),
+];
+
+const BUILTIN_TITANS: [(&str, &str); 12] = [
+    ("BUSINESS_SIMMONS", "BUSINESS"),
+    ("JOURNALIST_RITA", "JOURNALIST"),
+    ("KKK_OLBERICH", "KKK"),
+    ("IRELAND_DOYLE", "BOOTLEG"),
+    ("ITALIAN_ALBERTO", "ITALIAN"),
+    ("ITALIAN_ARMANDO", "ITALIAN"),
+    ("REICH_ZIBBELE", "REICH"),
+    ("REICH_STELM", "REICH"),
+    ("POLICE_WALLIS", "POLICE"),
+    ("MAYOR_CARPENTER", "CARPENTER"),
+    ("MAYOR_STRIPES", "STRIPE"),
+    ("PROSECUTOR_STRIPES", "STRIPE"),
+];
+
+/// Department id, display name and highest research bonus. Every department
+/// uses the same cap here; an install's department config can set it per
+/// department.
+const BUILTIN_DEPARTMENTS: [(&str, &str, i64); 10] = [
+    ("ESCORT", "Services", 5),
+    ("INFRASTRUCTURE", "Maintenance", 5),
+    ("POSTPRODUCTION", "Post-Production", 5),
+    ("PREPRODUCTION", "Pre-Production", 5),
+    ("PRODUCERS", "Producers Offices", 5),
+    ("PRODUCTION", "Production", 5),
+    ("RELEASE", "Distribution", 5),
+    ("SCRIPT", "Script Department", 5),
+    ("SECURITY", "Security", 5),
+    ("TECH", "Engineering", 5),
+];
+
+/// A definition listed by a [`Catalogue`].
+pub trait CatalogueEntry: Sized {
+    type Id: PartialEq + ?Sized;
+
+    fn id(&self) -> &Self::Id;
+
+    /// Reads the definitions from the game install. `None`, the default, means the
+    /// game has nothing to read and the builtin table is used.
+    fn from_game_data(_game_path: &str) -> Option<Vec<Self>> {
+        None
+    }
+
+    /// The definitions the editor ships with, used when the game is not found or
+    /// its files cannot be read.
+    fn builtin() -> Vec<Self>;
+}
+
+/// The game definitions edits are validated against. `from_game_data` tells
+/// whether they were read from the install or came from the builtin tables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalogue<T> {
+    pub entries: Vec<T>,
+    pub from_game_data: bool,
+}
+
+impl<T: CatalogueEntry> Catalogue<T> {
+    pub fn load(game_path: Option<&str>) -> Self {
+        match game_path.and_then(T::from_game_data) {
+            Some(entries) if !entries.is_empty() => Self {
+                entries,
+                from_game_data: true,
+            },
+            _ => Self {
+                entries: T::builtin(),
+                from_game_data: false,
+            },
+        }
+    }
+
+    pub fn get(&self, id: &T::Id) -> Option<&T> {
+        self.entries.iter().find(|entry| entry.id() == id)
+    }
+
+    pub fn contains(&self, id: &T::Id) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+/// Parses the entries of a config file, skipping the ones `parse` rejects.
+fn read_config<T>(
+    game_path: &str,
+    file_name: &str,
+    key: &str,
+    parse: impl Fn(&Value) -> Option<T>,
+) -> Option<Vec<T>> {
+    let data = load_config_file(game_path, file_name)?;
+    let entries = config_entries(&data, key)?;
+    Some(entries.iter().filter_map(parse).collect())
+}
+
+pub type TraitCatalogue = Catalogue<TraitInfo>;
+pub type GenreCatalogue = Catalogue<GenreInfo>;
+pub type StudioLogoCatalogue = Catalogue<StudioLogoInfo>;
+pub type ResourceCatalogue = Catalogue<ResourceInfo>;
+pub type TitanCatalogue = Catalogue<TitanInfo>;
+pub type DepartmentCatalogue = Catalogue<DepartmentInfo>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitInfo {
+    pub id: String,
+    pub conflicts_with: Option<String>,
+}
+
+impl CatalogueEntry for TraitInfo {
+    type Id = str;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn from_game_data(game_path: &str) -> Option<Vec<Self>> {
+        read_config(
+            game_path,
+            TRAITS_CONFIG_FILE,
+            TRAITS_CONFIG_KEY,
+            TraitInfo::from_config,
+        )
+    }
+
+    fn builtin() -> Vec<Self> {
+        BUILTIN_TRAITS
+            .iter()
+            .map(|id| TraitInfo {
+                id: id.to_string(),
+                conflicts_with: builtin_conflict(id).map(|c| c.to_string()),
+            })
+            .collect()
+    }
+}
+
+impl TraitInfo {
+    /// Entries name the opposite trait under `opposite` or `conflictsWith`. When
+    /// neither is given the builtin pairs are used.
+    fn from_config(entry: &Value) -> Option<Self> {
+        let id = config_entry_id(entry)?;
+        let conflict = ["opposite", "conflictsWith"]
+            .iter()
+            .find_map(|field| entry.get(*field).and_then(|v| v.as_str()))
+            .or_else(|| builtin_conflict(id));
+        Some(TraitInfo {
+            id: id.to_string(),
+            conflicts_with: conflict.map(|c| c.to_string()),
+        })
+    }
+}
+
+impl TraitCatalogue {
+    pub fn conflict_of(&self, trait_id: &str) -> Option<&str> {
+        self.get(trait_id).and_then(|t| t.conflicts_with.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenreInfo {
+    pub id: String,
+}
+
+impl CatalogueEntry for GenreInfo {
+    type Id = str;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn builtin() -> Vec<Self> {
+        BUILTIN_GENRES
+            .iter()
+            .map(|id| GenreInfo { id: id.to_string() })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudioLogoInfo {
+    pub id: i64,
+}
+
+impl CatalogueEntry for StudioLogoInfo {
+    type Id = i64;
+
+    fn id(&self) -> &i64 {
+        &self.id
+    }
+
+    /// One entry per `PL<id>` image in the install's studio logo folder.
+    fn from_game_data(game_path: &str) -> Option<Vec<Self>> {
+        let files = std::fs::read_dir(paths::studio_logos_path(game_path)).ok()?;
+        let mut ids: Vec<i64> = files
+            .filter_map(|file| logo_id_from_file_name(&file.ok()?.file_name().to_string_lossy()))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        Some(ids.into_iter().map(|id| StudioLogoInfo { id }).collect())
+    }
+
+    fn builtin() -> Vec<Self> {
+        (0..BUILTIN_STUDIO_LOGO_COUNT)
+            .map(|id| StudioLogoInfo { id })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceInfo {
+    pub id: String,
+    pub name: String,
+    pub max: i64,
+}
+
+impl CatalogueEntry for ResourceInfo {
+    type Id = str;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn builtin() -> Vec<Self> {
+        BUILTIN_RESOURCES
+            .iter()
+            .map(|(id, name, max)| ResourceInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                max: *max,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TitanInfo {
+    pub id: String,
+    pub faction: String,
+}
+
+impl CatalogueEntry for TitanInfo {
+    type Id = str;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn from_game_data(game_path: &str) -> Option<Vec<Self>> {
+        read_config(
+            game_path,
+            TITANS_CONFIG_FILE,
+            TITANS_CONFIG_KEY,
+            TitanInfo::from_config,
+        )
+    }
+
+    fn builtin() -> Vec<Self> {
+        BUILTIN_TITANS
+            .iter()
+            .map(|(id, faction)| TitanInfo {
+                id: id.to_string(),
+                faction: faction.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl TitanInfo {
+    /// Entries without a `faction` take the builtin one, or else the part of the
+    /// id before the first underscore, which is how the game names its titans.
+    fn from_config(entry: &Value) -> Option<Self> {
+        let id = config_entry_id(entry)?;
+        let faction = entry
+            .get("faction")
+            .and_then(|v| v.as_str())
+            .or_else(|| builtin_faction(id))
+            .unwrap_or_else(|| id.split('_').next().unwrap_or(id));
+        Some(TitanInfo {
+            id: id.to_string(),
+            faction: faction.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepartmentInfo {
+    pub id: String,
+    pub name: String,
+    pub max_time_bonus: i64,
+}
+
+impl CatalogueEntry for DepartmentInfo {
+    type Id = str;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn from_game_data(game_path: &str) -> Option<Vec<Self>> {
+        read_config(
+            game_path,
+            DEPARTMENTS_CONFIG_FILE,
+            DEPARTMENTS_CONFIG_KEY,
+            DepartmentInfo::from_config,
+        )
+    }
+
+    fn builtin() -> Vec<Self> {
+        BUILTIN_DEPARTMENTS
+            .iter()
+            .map(|(id, name, max_time_bonus)| DepartmentInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                max_time_bonus: *max_time_bonus,
+            })
+            .collect()
+    }
+}
+
+impl DepartmentInfo {
+    /// Entries without a `name` or `maxTimeBonus` take them from the builtin row,
+    /// and departments the editor does not know are skipped unless the entry gives
+    /// its own cap.
+    fn from_config(entry: &Value) -> Option<Self> {
+        let id = config_entry_id(entry)?;
+        let builtin = BUILTIN_DEPARTMENTS.iter().find(|(dept, _, _)| *dept == id);
+        let name = entry
+            .get("name")
+            .and_then(|v| v.as_str())
+            .or(builtin.map(|(_, name, _)| *name))
+            .unwrap_or(id);
+        let max_time_bonus = entry
+            .get("maxTimeBonus")
+            .and_then(|v| v.as_i64())
+            .or(builtin.map(|(_, _, max)| *max))?;
+        Some(DepartmentInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            max_time_bonus,
+        })
+    }
+}
+
+fn logo_id_from_file_name(file_name: &str) -> Option<i64> {
+    let (stem, _extension) = file_name.rsplit_once('.')?;
+    stem.strip_prefix(STUDIO_LOGO_FILE_PREFIX)?.parse().ok()
+}
+
+fn builtin_faction(titan_id: &str) -> Option<&'static str> {
+    BUILTIN_TITANS
+        .iter()
+        .find(|(id, _)| *id == titan_id)
+        .map(|(_, faction)| *faction)
+}
+
+fn builtin_conflict(trait_id: &str) -> Option<&'static str> {
+    BUILTIN_TRAIT_CONFLICTS.iter().find_map(|(a, b)| {
+        if *a == trait_id {
+            Some(*b)
+        } else if *b == trait_id {
+            Some(*a)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn missing_game_falls_back_to_builtin() {
+        for game_path in [None, Some("/nonexistent/Hollywood Animal")] {
+            let traits = TraitCatalogue::load(game_path);
+            assert!(!traits.from_game_data);
+            assert_eq!(traits.entries.len(), BUILTIN_TRAITS.len());
+            assert_eq!(traits.conflict_of("LAZY"), Some("HARDWORKING"));
+        }
+    }
+
+    #[test]
+    fn trait_entries_are_read_from_config() {
+        let data = json!({ "labels": [
+            "LAZY",
+            { "id": "BRAVE", "opposite": "COWARD" },
+            { "id": "COWARD", "conflictsWith": "BRAVE" },
+            { "name": "no id" },
+        ]});
+        let traits: Vec<TraitInfo> = config_entries(&data, TRAITS_CONFIG_KEY)
+            .unwrap()
+            .iter()
+            .filter_map(TraitInfo::from_config)
+            .collect();
+
+        let conflicts: Vec<_> = traits
+            .iter()
+            .map(|t| (t.id.as_str(), t.conflicts_with.as_deref()))
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                ("LAZY", Some("HARDWORKING")),
+                ("BRAVE", Some("COWARD")),
+                ("COWARD", Some("BRAVE")),
+            ]
+        );
+    }
+
+    #[test]
+    fn logo_ids_come_from_logo_file_names() {
+        assert_eq!(logo_id_from_file_name("PL0.png"), Some(0));
+        assert_eq!(logo_id_from_file_name("PL14.png"), Some(14));
+        assert_eq!(logo_id_from_file_name("PL3.png.meta"), None);
+        assert_eq!(logo_id_from_file_name("GB.png"), None);
+        assert_eq!(logo_id_from_file_name("PL"), None);
+    }
+
+    #[test]
+    fn department_caps_are_read_from_config() {
+        let data = json!({ "departments": [
+            { "id": "SCRIPT", "maxTimeBonus": 3 },
+            "TECH",
+            { "id": "CASTING", "name": "Casting", "maxTimeBonus": 4 },
+            "UNKNOWN",
+        ]});
+        let departments: Vec<DepartmentInfo> = config_entries(&data, DEPARTMENTS_CONFIG_KEY)
+            .unwrap()
+            .iter()
+            .filter_map(DepartmentInfo::from_config)
+            .collect();
+
+        let caps: Vec<_> = departments
+            .iter()
+            .map(|d| (d.id.as_str(), d.name.as_str(), d.max_time_bonus))
+            .collect();
+        assert_eq!(
+            caps,
+            [
+                ("SCRIPT", "Script Department", 3),
+                ("TECH", "Engineering", 5),
+                ("CASTING", "Casting", 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn titan_entries_are_read_from_config() {
+        let data = json!([
+            "MAYOR_STRIPES",
+            { "id": "UNION_HARLAN", "faction": "UNION" },
+            "MOB_VITO",
+        ]);
+        let titans: Vec<TitanInfo> = config_entries(&data, TITANS_CONFIG_KEY)
+            .unwrap()
+            .iter()
+            .filter_map(TitanInfo::from_config)
+            .collect();
+
+        let factions: Vec<_> = titans
+            .iter()
+            .map(|t| (t.id.as_str(), t.faction.as_str()))
+            .collect();
+        assert_eq!(
+            factions,
+            [
+                ("MAYOR_STRIPES", "STRIPE"),
+                ("UNION_HARLAN", "UNION"),
+                ("MOB_VITO", "MOB"),
+            ]
+        );
+    }
+}
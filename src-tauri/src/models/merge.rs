@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A value both the editor and the game changed. `pointer` locates it in the
+/// merged `stateJson`, and a missing side means that side removed the value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConflict {
+    pub path: String,
+    pub pointer: String,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeReport {
+    pub dry_run: bool,
+    pub conflicts: Vec<MergeConflict>,
+    pub checkpoint: Option<String>,
+}
@@ -1,7 +1,25 @@
+pub mod bundle;
+pub mod catalogue;
+pub mod merge;
 pub mod person;
+pub mod preset;
+pub mod raw;
+pub mod report;
+pub mod roster;
 pub mod save_info;
+pub mod script;
 pub mod studio;
+pub mod white_tag;
 
+pub use bundle::*;
+pub use catalogue::*;
+pub use merge::*;
 pub use person::*;
+pub use preset::*;
+pub use raw::*;
+pub use report::*;
+pub use roster::*;
 pub use save_info::*;
+pub use script::*;
 pub use studio::*;
+pub use white_tag::*;
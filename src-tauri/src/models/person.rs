@@ -1,7 +1,13 @@
+use chrono::{Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::PLAYER_STUDIO_ID;
+use crate::error::{AppError, AppResult};
+use crate::utils::{json_number, parse_save_date};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PersonUpdate {
     #[serde(rename = "firstNameId")]
     pub first_name_id: Option<String>,
@@ -26,28 +32,81 @@ pub struct PersonUpdate {
     pub add_trait: Option<String>,
     #[serde(rename = "removeTrait")]
     pub remove_trait: Option<String>,
+    #[serde(rename = "resolveTraitConflicts")]
+    pub resolve_trait_conflicts: Option<bool>,
     #[serde(rename = "addGenre")]
     pub add_genre: Option<String>,
     #[serde(rename = "removeGenre")]
     pub remove_genre: Option<String>,
+    #[serde(rename = "genreValues")]
+    pub genre_values: Option<BTreeMap<String, f64>>,
     #[serde(rename = "portraitBaseId")]
     pub portrait_base_id: Option<i32>,
     #[serde(rename = "birthYear")]
     pub birth_year: Option<i32>,
     #[serde(rename = "isShady")]
     pub is_shady: Option<bool>,
+    #[serde(rename = "contractMonthlySalary")]
+    pub contract_monthly_salary: Option<f64>,
+    #[serde(rename = "contractInitialFee")]
+    pub contract_initial_fee: Option<f64>,
     #[serde(rename = "bonusCardMoney")]
     pub bonus_card_money: Option<i32>,
     #[serde(rename = "bonusCardInfluencePoints")]
     pub bonus_card_influence_points: Option<i32>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfessionSkill {
+    pub profession: String,
+    pub skill: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DemotedHolder {
+    pub person_id: String,
+    pub new_role: Option<String>,
+    pub released: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeadershipChange {
+    pub person_id: String,
+    pub role: String,
+    pub previous_professions: Vec<String>,
+    pub demoted: Vec<DemotedHolder>,
+}
+
+const INDEFINITE_CONTRACT_TYPE: i64 = 2;
+
+/// Skill given to the base profession a leadership holder takes on stepping down.
+const STEP_DOWN_SKILL: f64 = 0.0;
+
+const BASE_PROFESSIONS: [&str; 8] = [
+    "Actor",
+    "Director",
+    "Producer",
+    "Scriptwriter",
+    "FilmEditor",
+    "Composer",
+    "Cinematographer",
+    "Agent",
+];
+
 const CAPTAIN_PROFESSIONS: [&str; 4] = ["CptHR", "CptLawyer", "CptFinancier", "CptPR"];
 
 const LIEUTENANT_PROFESSIONS: [&str; 11] = [
-    "LieutScript", "LieutPrep", "LieutProd", "LieutPost", "LieutRelease",
-    "LieutSecurity", "LieutProducers", "LieutInfrastructure", "LieutTech",
-    "LieutMuseum", "LieutEscort"
+    "LieutScript",
+    "LieutPrep",
+    "LieutProd",
+    "LieutPost",
+    "LieutRelease",
+    "LieutSecurity",
+    "LieutProducers",
+    "LieutInfrastructure",
+    "LieutTech",
+    "LieutMuseum",
+    "LieutEscort",
 ];
 
 pub fn has_profession(character: &Value, profession: &str) -> bool {
@@ -58,13 +117,83 @@ pub fn has_profession(character: &Value, profession: &str) -> bool {
             if profession == "Executive" {
                 CAPTAIN_PROFESSIONS.iter().any(|cpt| map.contains_key(*cpt))
             } else if profession == "DepartmentHead" {
-                LIEUTENANT_PROFESSIONS.iter().any(|lt| map.contains_key(*lt))
+                LIEUTENANT_PROFESSIONS
+                    .iter()
+                    .any(|lt| map.contains_key(*lt))
             } else {
                 map.contains_key(profession)
             }
         })
 }
 
+pub fn get_lieutenant_profession(character: &Value) -> Option<&str> {
+    character
+        .get("professions")
+        .and_then(|p| p.as_object())
+        .and_then(|map| {
+            LIEUTENANT_PROFESSIONS
+                .iter()
+                .find(|lt| map.contains_key(**lt))
+                .copied()
+        })
+}
+
+pub fn is_leadership_profession(profession: &str) -> bool {
+    CAPTAIN_PROFESSIONS.contains(&profession) || LIEUTENANT_PROFESSIONS.contains(&profession)
+}
+
+pub fn get_leadership_profession(character: &Value) -> Option<&str> {
+    character
+        .get("professions")
+        .and_then(|p| p.as_object())
+        .and_then(|map| map.keys().find(|p| is_leadership_profession(p)))
+        .map(|s| s.as_str())
+}
+
+/// Removes the captain or lieutenant role from the character's professions,
+/// returning the role that was held.
+pub fn remove_leadership_role(character: &mut Value) -> Option<String> {
+    let role = get_leadership_profession(character)?.to_string();
+    character["professions"].as_object_mut()?.remove(&role);
+    Some(role)
+}
+
+/// The base profession a captain or lieutenant takes when they give up their role.
+/// A leadership role can't be combined with other professions, so the holder is
+/// left with none and no tab or command could reach them; `base_profession` is
+/// required for them. Characters without a role need none and get `None`.
+pub fn leadership_replacement<'a>(
+    character: &Value,
+    base_profession: Option<&'a str>,
+) -> AppResult<Option<&'a str>> {
+    let Some(role) = get_leadership_profession(character) else {
+        return Ok(None);
+    };
+    match base_profession {
+        Some(profession) if BASE_PROFESSIONS.contains(&profession) => Ok(Some(profession)),
+        Some(profession) => Err(AppError::Invalid(format!(
+            "{} is not a base profession",
+            profession
+        ))),
+        None => Err(AppError::Invalid(format!(
+            "Person holds the {} role; choose a base profession for them to take instead",
+            role
+        ))),
+    }
+}
+
+/// Replaces the character's captain or lieutenant role with `base_profession`,
+/// returning the role that was held.
+pub fn step_down_from_leadership(character: &mut Value, base_profession: &str) -> Option<String> {
+    let role = remove_leadership_role(character)?;
+    character["professions"][base_profession] = serde_json::json!(STEP_DOWN_SKILL);
+    Some(role)
+}
+
+pub fn is_known_profession(profession: &str) -> bool {
+    BASE_PROFESSIONS.contains(&profession) || is_leadership_profession(profession)
+}
+
 pub fn get_captain_profession(character: &Value) -> Option<&str> {
     character
         .get("professions")
@@ -77,9 +206,46 @@ pub fn get_captain_profession(character: &Value) -> Option<&str> {
         })
 }
 
-pub fn count_profession(characters: &[Value], profession: &str) -> usize {
-    characters
-        .iter()
-        .filter(|c| has_profession(c, profession))
-        .count()
+/// Picks the profession a character is reported under: the leadership role if
+/// they hold one, otherwise their first talent profession.
+pub fn primary_profession(character: &Value) -> Option<&str> {
+    let map = character.get("professions").and_then(|p| p.as_object())?;
+    get_leadership_profession(character)
+        .or_else(|| {
+            BASE_PROFESSIONS
+                .iter()
+                .find(|p| map.contains_key(**p))
+                .copied()
+        })
+        .or_else(|| map.keys().next().map(|k| k.as_str()))
+}
+
+pub fn profession_skill(character: &Value, profession: &str) -> Option<f64> {
+    character
+        .get("professions")
+        .and_then(|p| p.get(profession))
+        .and_then(json_number)
+}
+
+pub fn in_player_studio(character: &Value) -> bool {
+    character.get("studioId").and_then(|s| s.as_str()) == Some(PLAYER_STUDIO_ID)
+}
+
+pub fn contract_field(character: &Value, field: &str) -> f64 {
+    character
+        .get("contract")
+        .and_then(|c| c.get(field))
+        .and_then(json_number)
+        .unwrap_or(0.0)
+}
+
+/// Contracts run `amount` years from `dateOfSigning`; indefinite contracts have no end.
+pub fn contract_end_date(character: &Value) -> Option<NaiveDate> {
+    let contract = character.get("contract").filter(|c| c.is_object())?;
+    if contract.get("contractType").and_then(|t| t.as_i64()) == Some(INDEFINITE_CONTRACT_TYPE) {
+        return None;
+    }
+    let signed = parse_save_date(contract.get("dateOfSigning")?.as_str()?)?;
+    let years = contract.get("amount").and_then(|a| a.as_u64())?;
+    signed.checked_add_months(Months::new(u32::try_from(years * 12).ok()?))
 }
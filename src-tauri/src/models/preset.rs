@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use super::{PersonUpdate, StudioUpdate};
+
+/// A named set of edits stored as `<id>.toml` or `<id>.json` in the presets folder
+/// of the app config directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub operations: Vec<PresetOperation>,
+}
+
+/// A single preset step, tagged by the command it mirrors. Omitting `titanId` or
+/// `department` targets every entry in the matching catalogue, and omitting the
+/// time bonus `value` uses each department's maximum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum PresetOperation {
+    #[serde(rename = "update_studio")]
+    Studio(StudioUpdate),
+    #[serde(rename = "update_resource")]
+    Resource {
+        #[serde(rename = "resourceId")]
+        resource_id: String,
+        value: i64,
+        #[serde(default)]
+        force: bool,
+    },
+    #[serde(rename = "update_titan")]
+    Titan {
+        #[serde(rename = "titanId")]
+        titan_id: Option<String>,
+        value: i64,
+        item1: Option<i64>,
+        #[serde(default)]
+        unlock: bool,
+    },
+    #[serde(rename = "update_time_bonus")]
+    TimeBonus {
+        department: Option<String>,
+        value: Option<i64>,
+    },
+    #[serde(rename = "update_persons")]
+    Persons {
+        profession: String,
+        #[serde(rename = "studioId")]
+        studio_id: Option<String>,
+        update: Box<PersonUpdate>,
+    },
+}
+
+impl PresetOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Studio(_) => "update_studio",
+            Self::Resource { .. } => "update_resource",
+            Self::Titan { .. } => "update_titan",
+            Self::TimeBonus { .. } => "update_time_bonus",
+            Self::Persons { .. } => "update_persons",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresetSummary {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub operation_count: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresetStepResult {
+    pub index: usize,
+    pub op: String,
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresetReport {
+    pub id: String,
+    pub dry_run: bool,
+    pub steps: Vec<PresetStepResult>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PatchResult {
+    pub applied: usize,
+    pub new_issues: Vec<String>,
+    pub checkpoint: String,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfessionPayroll {
+    pub profession: String,
+    pub headcount: usize,
+    pub monthly_salary: f64,
+    pub initial_fees: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpiringContract {
+    pub person_id: String,
+    pub profession: String,
+    pub monthly_salary: f64,
+    pub end_date: String,
+    pub days_left: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PayrollReport {
+    pub headcount: usize,
+    pub monthly_salary: f64,
+    pub initial_fees: f64,
+    pub by_profession: Vec<ProfessionPayroll>,
+    pub expiring: Vec<ExpiringContract>,
+    pub budget: i64,
+    pub cash: i64,
+    pub salary_share_of_budget: Option<f64>,
+    pub months_covered_by_cash: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScoutSortKey {
+    #[default]
+    Skill,
+    Headroom,
+    Art,
+    Com,
+    Age,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoutedPerson {
+    pub person_id: String,
+    pub skill: f64,
+    pub limit: f64,
+    pub headroom: f64,
+    pub art: f64,
+    pub com: f64,
+    pub age: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoutingGroup {
+    pub profession: String,
+    pub owner: String,
+    pub candidates: Vec<ScoutedPerson>,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use super::PersonUpdate;
+
+/// One spreadsheet row of the roster CSV. Traits are `;`-separated ids and genres
+/// are `;`-separated `GENRE:value` pairs.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RosterRow {
+    pub id: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub studio: String,
+    pub age: Option<i64>,
+    pub skill: f64,
+    pub limit: f64,
+    pub mood: f64,
+    pub attitude: f64,
+    pub self_esteem: f64,
+    pub readiness: f64,
+    pub art: Option<f64>,
+    pub com: Option<f64>,
+    pub traits: String,
+    pub genres: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RosterRowChange {
+    pub row: usize,
+    pub person_id: String,
+    pub updates: Vec<PersonUpdate>,
+    pub error: Option<String>,
+}
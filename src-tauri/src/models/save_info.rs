@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SaveInfo {
+    pub handle: String,
     pub current_date: String,
     pub player_studio_name: String,
     pub actors_count: usize,
@@ -22,3 +23,18 @@ pub struct SaveInfo {
     pub influence: i64,
     pub studio_logo_id: i64,
 }
+
+/// A save that is currently open, identified by the handle commands accept.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenSave {
+    pub handle: String,
+    pub path: String,
+    pub active: bool,
+}
+
+/// Payload of the event emitted when another program changes an open save's file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFileChanged {
+    pub handle: String,
+    pub path: String,
+}
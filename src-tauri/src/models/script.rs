@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScriptOutput {
+    pub output: Vec<String>,
+    pub result: String,
+    pub dry_run: bool,
+    pub checkpoint: Option<String>,
+}
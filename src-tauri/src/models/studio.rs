@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+pub const PLAYER_STUDIO_ID: &str = "PL";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StudioUpdate {
     pub budget: Option<i64>,
     pub cash: Option<i64>,
     pub reputation: Option<f64>,
     pub influence: Option<i64>,
+    #[serde(rename = "studioName")]
+    pub studio_name: Option<String>,
+    #[serde(rename = "studioLogoId")]
+    pub studio_logo_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,3 +32,31 @@ pub struct CompetitorUpdate {
     #[serde(rename = "budgetCheatsRemaining")]
     pub budget_cheats_remaining: Option<i64>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeBonusStatus {
+    pub department: String,
+    pub name: String,
+    pub value: i64,
+    pub has_entry: bool,
+    pub known: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TitanState {
+    pub id: String,
+    pub faction: Option<String>,
+    pub unlocked: bool,
+    pub item1: Option<i64>,
+    pub item2: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferResult {
+    pub person_id: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub contract_created: bool,
+    pub contract_cleared: bool,
+    pub movies_left: Vec<String>,
+}
@@ -0,0 +1,370 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::error::{AppError, AppResult};
+use crate::utils::{json_number, set_number, NumberFormat};
+
+const DEFAULT_DATE_ADDED: &str = "1929-01-01T00:00:00";
+const BASE_MOVIE_ID: i64 = 0;
+const BASE_SOURCE_TYPE: i64 = 0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverallValueEntry {
+    pub movie_id: i64,
+    pub source_type: i64,
+    pub value: f64,
+    pub date_added: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersonGenre {
+    pub genre: String,
+    pub value: f64,
+    pub overall_values: Vec<OverallValueEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WhiteTagInfo {
+    pub id: String,
+    pub value: f64,
+    pub is_overall: bool,
+    pub movie_id: i64,
+    pub date_added: Option<String>,
+    pub overall_values: Vec<OverallValueEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OverallValueEdit {
+    #[serde(rename = "movieId")]
+    pub movie_id: i64,
+    #[serde(rename = "sourceType")]
+    pub source_type: i64,
+    pub value: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WhiteTagUpdate {
+    pub value: Option<f64>,
+    #[serde(rename = "isOverall")]
+    pub is_overall: Option<bool>,
+    pub remove: Option<bool>,
+    pub entries: Option<Vec<OverallValueEdit>>,
+}
+
+pub fn white_tag_info(tag_id: &str, tag: &Value) -> WhiteTagInfo {
+    WhiteTagInfo {
+        id: tag_id.to_string(),
+        value: white_tag_value(tag),
+        is_overall: tag
+            .get("IsOverall")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        movie_id: tag
+            .get("movieId")
+            .and_then(|m| m.as_i64())
+            .unwrap_or(BASE_MOVIE_ID),
+        date_added: tag
+            .get("dateAdded")
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_string()),
+        overall_values: overall_value_entries(tag),
+    }
+}
+
+pub fn remove_white_tag(person: &mut Value, tag_id: &str) {
+    if let Some(tags) = person
+        .get_mut("whiteTagsNEW")
+        .and_then(|w| w.as_object_mut())
+    {
+        tags.remove(tag_id);
+    }
+}
+
+pub fn upsert_white_tag(person: &mut Value, tag_id: &str, value: f64) {
+    if person.get("whiteTagsNEW").is_none() {
+        person["whiteTagsNEW"] = serde_json::json!({});
+    }
+    let tags = person["whiteTagsNEW"].as_object_mut().unwrap();
+
+    match tags.get_mut(tag_id) {
+        Some(tag) => {
+            set_number(tag, "value", value, NumberFormat::Float);
+            if let Some(arr) = tag.get_mut("overallValues").and_then(|o| o.as_array_mut()) {
+                if let Some(entry) = arr.iter_mut().find(|ov| {
+                    ov.get("movieId").and_then(|m| m.as_i64()) == Some(BASE_MOVIE_ID)
+                        && ov.get("sourceType").and_then(|s| s.as_i64()) == Some(BASE_SOURCE_TYPE)
+                }) {
+                    set_number(entry, "value", value, NumberFormat::Float);
+                }
+            }
+        }
+        None => {
+            tags.insert(
+                tag_id.to_string(),
+                serde_json::json!({
+                    "id": tag_id,
+                    "value": value,
+                    "dateAdded": DEFAULT_DATE_ADDED,
+                    "movieId": BASE_MOVIE_ID,
+                    "IsOverall": false,
+                    "overallValues": [{
+                        "movieId": BASE_MOVIE_ID,
+                        "sourceType": BASE_SOURCE_TYPE,
+                        "value": value,
+                        "dateAdded": DEFAULT_DATE_ADDED
+                    }]
+                }),
+            );
+        }
+    }
+}
+
+pub fn white_tag_value(tag: &Value) -> f64 {
+    tag.get("value").and_then(json_number).unwrap_or(0.0)
+}
+
+pub fn overall_value_entries(tag: &Value) -> Vec<OverallValueEntry> {
+    tag.get("overallValues")
+        .and_then(|o| o.as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|ov| OverallValueEntry {
+                    movie_id: ov.get("movieId").and_then(|m| m.as_i64()).unwrap_or(0),
+                    source_type: ov.get("sourceType").and_then(|s| s.as_i64()).unwrap_or(0),
+                    value: ov.get("value").and_then(json_number).unwrap_or(0.0),
+                    date_added: ov
+                        .get("dateAdded")
+                        .and_then(|d| d.as_str())
+                        .map(|d| d.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks a batch of `overallValues` edits against the tag before any of them is
+/// applied, so a rejected entry leaves the tag untouched.
+pub fn validate_overall_edits(
+    tag: Option<&Value>,
+    tag_id: &str,
+    edits: &[OverallValueEdit],
+) -> AppResult<()> {
+    let mut present: HashSet<(i64, i64)> = tag
+        .map(overall_value_entries)
+        .unwrap_or_default()
+        .iter()
+        .map(|e| (e.movie_id, e.source_type))
+        .collect();
+    for edit in edits {
+        let key = (edit.movie_id, edit.source_type);
+        if edit.value.is_some() {
+            present.insert(key);
+            continue;
+        }
+        if key == (BASE_MOVIE_ID, BASE_SOURCE_TYPE) {
+            return Err(base_entry_removal(tag_id));
+        }
+        if !present.remove(&key) {
+            return Err(missing_entry(tag_id, edit.movie_id, edit.source_type));
+        }
+    }
+    Ok(())
+}
+
+/// Sets or removes (`value == None`) a single `overallValues` contribution of a
+/// white tag. The tag's own `value` moves by the same delta, so the overall
+/// value stays in step with its contributions.
+pub fn set_overall_value(
+    person: &mut Value,
+    tag_id: &str,
+    movie_id: i64,
+    source_type: i64,
+    value: Option<f64>,
+) -> AppResult<()> {
+    if value.is_none() && movie_id == BASE_MOVIE_ID && source_type == BASE_SOURCE_TYPE {
+        return Err(base_entry_removal(tag_id));
+    }
+
+    let tag = person
+        .get_mut("whiteTagsNEW")
+        .and_then(|w| w.get_mut(tag_id))
+        .ok_or_else(|| AppError::not_found("Tag", tag_id))?;
+
+    let position = tag
+        .get("overallValues")
+        .and_then(|o| o.as_array())
+        .and_then(|entries| {
+            entries.iter().position(|ov| {
+                ov.get("movieId").and_then(|m| m.as_i64()) == Some(movie_id)
+                    && ov.get("sourceType").and_then(|s| s.as_i64()) == Some(source_type)
+            })
+        });
+    if position.is_none() && value.is_none() {
+        return Err(missing_entry(tag_id, movie_id, source_type));
+    }
+
+    if !tag.get("overallValues").is_some_and(|o| o.is_array()) {
+        tag["overallValues"] = serde_json::json!([]);
+    }
+    let entries = tag["overallValues"].as_array_mut().unwrap();
+    let previous = position
+        .and_then(|i| entries[i].get("value"))
+        .and_then(json_number)
+        .unwrap_or(0.0);
+
+    match (position, value) {
+        (Some(i), Some(v)) => set_number(&mut entries[i], "value", v, NumberFormat::Float),
+        (Some(i), None) => {
+            entries.remove(i);
+        }
+        (None, Some(v)) => entries.push(serde_json::json!({
+            "movieId": movie_id,
+            "sourceType": source_type,
+            "value": v,
+            "dateAdded": DEFAULT_DATE_ADDED
+        })),
+        // Refused above, before anything was changed.
+        (None, None) => {}
+    }
+
+    let delta = value.unwrap_or(0.0) - previous;
+    let total = white_tag_value(tag) + delta;
+    set_number(tag, "value", total, NumberFormat::Float);
+    Ok(())
+}
+
+/// Drops the `overallValues` contributions that come from movies, taking their
+/// share out of each tag's overall value. Used when a character leaves the save
+/// those movies belong to.
+pub fn strip_movie_history(person: &mut Value) {
+    let Some(tags) = person
+        .get_mut("whiteTagsNEW")
+        .and_then(|w| w.as_object_mut())
+    else {
+        return;
+    };
+    for tag in tags.values_mut() {
+        let Some(entries) = tag.get_mut("overallValues").and_then(|o| o.as_array_mut()) else {
+            continue;
+        };
+        let mut removed = 0.0;
+        entries.retain(|ov| {
+            let from_movie = ov
+                .get("movieId")
+                .and_then(|m| m.as_i64())
+                .unwrap_or(BASE_MOVIE_ID)
+                != BASE_MOVIE_ID;
+            if from_movie {
+                removed += ov.get("value").and_then(json_number).unwrap_or(0.0);
+            }
+            !from_movie
+        });
+        if removed != 0.0 {
+            let total = white_tag_value(tag) - removed;
+            set_number(tag, "value", total, NumberFormat::Float);
+        }
+        if tag.get("movieId").is_some() {
+            tag["movieId"] = serde_json::json!(BASE_MOVIE_ID);
+        }
+    }
+}
+
+fn base_entry_removal(tag_id: &str) -> AppError {
+    AppError::Invalid(format!(
+        "The base entry of {} cannot be removed; remove the tag instead",
+        tag_id
+    ))
+}
+
+fn missing_entry(tag_id: &str, movie_id: i64, source_type: i64) -> AppError {
+    AppError::Invalid(format!(
+        "Tag {} has no entry for movie {} / source {}",
+        tag_id, movie_id, source_type
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn person_with_tag() -> Value {
+        json!({
+            "id": 1,
+            "whiteTagsNEW": {
+                "COM": {
+                    "id": "COM",
+                    "value": 5.0,
+                    "overallValues": [
+                        { "movieId": 0, "sourceType": 0, "value": 3.0 },
+                        { "movieId": 7, "sourceType": 1, "value": 2.0 }
+                    ]
+                }
+            }
+        })
+    }
+
+    fn tag(person: &Value) -> &Value {
+        &person["whiteTagsNEW"]["COM"]
+    }
+
+    fn entry_values(person: &Value) -> Vec<(i64, i64, f64)> {
+        overall_value_entries(tag(person))
+            .iter()
+            .map(|e| (e.movie_id, e.source_type, e.value))
+            .collect()
+    }
+
+    #[test]
+    fn changing_an_entry_moves_the_total_by_the_same_delta() {
+        let mut person = person_with_tag();
+
+        set_overall_value(&mut person, "COM", 7, 1, Some(4.5)).unwrap();
+
+        assert_eq!(white_tag_value(tag(&person)), 7.5);
+        assert_eq!(entry_values(&person), [(0, 0, 3.0), (7, 1, 4.5)]);
+    }
+
+    #[test]
+    fn adding_and_removing_entries_adjusts_the_total() {
+        let mut person = person_with_tag();
+
+        set_overall_value(&mut person, "COM", 9, 2, Some(1.5)).unwrap();
+        assert_eq!(white_tag_value(tag(&person)), 6.5);
+
+        set_overall_value(&mut person, "COM", 7, 1, None).unwrap();
+        assert_eq!(white_tag_value(tag(&person)), 4.5);
+        assert_eq!(entry_values(&person), [(0, 0, 3.0), (9, 2, 1.5)]);
+    }
+
+    #[test]
+    fn refused_edits_leave_the_tag_unchanged() {
+        let mut person = person_with_tag();
+        let before = person.clone();
+
+        assert!(set_overall_value(&mut person, "COM", 0, 0, None).is_err());
+        assert!(set_overall_value(&mut person, "COM", 8, 1, None).is_err());
+        assert!(set_overall_value(&mut person, "ART", 7, 1, Some(1.0)).is_err());
+        assert_eq!(person, before);
+    }
+
+    #[test]
+    fn batch_edits_are_checked_in_order() {
+        let com = tag(&person_with_tag()).clone();
+        let edit = |movie_id, source_type, value| OverallValueEdit {
+            movie_id,
+            source_type,
+            value,
+        };
+
+        let add_then_remove = [edit(9, 2, Some(1.0)), edit(9, 2, None)];
+        assert!(validate_overall_edits(Some(&com), "COM", &add_then_remove).is_ok());
+
+        let remove_twice = [edit(7, 1, None), edit(7, 1, None)];
+        assert!(validate_overall_edits(Some(&com), "COM", &remove_twice).is_err());
+
+        let remove_base = [edit(0, 0, None)];
+        assert!(validate_overall_edits(Some(&com), "COM", &remove_base).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::NaiveDate;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT, INT};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::commands::person::edit_person;
+use crate::commands::studio::{insert_titan, set_resource, set_titan};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    has_profession, primary_profession, profession_skill, upsert_white_tag, white_tag_value,
+    PersonUpdate, TraitCatalogue,
+};
+use crate::utils::{
+    age_on, get_state_json_mut, json_id_string, set_number, NumberFormat, SaveDataExt,
+};
+
+const MAX_OPERATIONS: u64 = 50_000_000;
+const MIN_SKILL: FLOAT = 0.0;
+const MAX_SKILL: FLOAT = 1.0;
+
+type SharedSave = Rc<RefCell<Value>>;
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// What a script printed and the value of its last expression.
+#[derive(Debug)]
+pub struct ScriptRun {
+    pub output: Vec<String>,
+    pub result: String,
+}
+
+/// A handle to one character of the save being scripted, exposed to Rhai as `Person`.
+#[derive(Clone)]
+struct PersonRef {
+    save: SharedSave,
+    index: usize,
+    today: NaiveDate,
+    traits: Rc<TraitCatalogue>,
+}
+
+impl PersonRef {
+    fn with<T>(&self, f: impl FnOnce(&mut Value) -> T) -> ScriptResult<T> {
+        let mut save = self.save.borrow_mut();
+        let person = save
+            .characters_mut()
+            .map_err(script_error)?
+            .get_mut(self.index)
+            .ok_or("Character no longer exists")?;
+        Ok(f(person))
+    }
+
+    /// Applies `update` the way the editor does, so traits are checked against the
+    /// catalogue and conflicting traits are refused.
+    fn update(&self, update: PersonUpdate) -> ScriptResult<()> {
+        self.with(|person| {
+            let profession = primary_profession(person).unwrap_or_default().to_string();
+            edit_person(person, &profession, &update, Some(&self.traits))
+        })?
+        .map_err(script_error)
+    }
+}
+
+/// Runs a Rhai script against `data`. Edits are made in place, so callers that want
+/// all-or-nothing behaviour should pass a copy and keep it only on success.
+///
+/// Besides the usual Rhai builtins, scripts get `characters()` (an array of `Person`
+/// handles), `get_studio`/`set_studio`, `get_resource`/`set_resource`,
+/// `get_titan`/`set_titan` and `game_date()`. A `Person` has `id`, `studio` and `age`
+/// properties, `has_profession`, `skill`/`set_skill`, `has_trait`/`add_trait`/
+/// `remove_trait`, `white_tag`/`set_white_tag`, and indexing (`p["mood"]`) for any
+/// other raw field. `add_trait` fails for traits missing from `traits` and for
+/// traits that conflict with one the person already has.
+pub fn run_script(data: &mut Value, source: &str, traits: TraitCatalogue) -> AppResult<ScriptRun> {
+    let today = data.game_date()?;
+    let save: SharedSave = Rc::new(RefCell::new(std::mem::take(data)));
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let traits = Rc::new(traits);
+
+    let result = {
+        let engine = build_engine(&save, &output, today, &traits);
+        engine.eval::<Dynamic>(source)
+    };
+    *data = save.take();
+
+    let result = result.map_err(|e| AppError::Invalid(format!("Script error: {}", e)))?;
+    Ok(ScriptRun {
+        output: output.take(),
+        result: if result.is_unit() {
+            String::new()
+        } else {
+            result.to_string()
+        },
+    })
+}
+
+fn build_engine(
+    save: &SharedSave,
+    output: &Rc<RefCell<Vec<String>>>,
+    today: NaiveDate,
+    traits: &Rc<TraitCatalogue>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let printed = output.clone();
+    engine.on_print(move |line| printed.borrow_mut().push(line.to_string()));
+    let debugged = output.clone();
+    engine.on_debug(move |line, _, _| debugged.borrow_mut().push(line.to_string()));
+
+    register_person(&mut engine);
+    register_save(&mut engine, save, today, traits);
+    engine
+}
+
+fn register_save(
+    engine: &mut Engine,
+    save: &SharedSave,
+    today: NaiveDate,
+    traits: &Rc<TraitCatalogue>,
+) {
+    let shared = save.clone();
+    let traits = traits.clone();
+    engine.register_fn("characters", move || -> ScriptResult<Array> {
+        let count = shared.borrow().characters().map_err(script_error)?.len();
+        Ok((0..count)
+            .map(|index| {
+                Dynamic::from(PersonRef {
+                    save: shared.clone(),
+                    index,
+                    today,
+                    traits: traits.clone(),
+                })
+            })
+            .collect())
+    });
+
+    engine.register_fn("game_date", move || today.format("%Y-%m-%d").to_string());
+
+    let shared = save.clone();
+    engine.register_fn("get_studio", move |field: &str| -> ScriptResult<Dynamic> {
+        let save = shared.borrow();
+        to_dynamic(save.state_json().map_err(script_error)?.get(field))
+    });
+
+    let shared = save.clone();
+    engine.register_fn(
+        "set_studio",
+        move |field: &str, value: Dynamic| -> ScriptResult<()> {
+            let mut save = shared.borrow_mut();
+            write_field(
+                get_state_json_mut(&mut save).map_err(script_error)?,
+                field,
+                value,
+            )
+        },
+    );
+
+    let shared = save.clone();
+    engine.register_fn(
+        "get_resource",
+        move |resource_id: &str| -> ScriptResult<INT> {
+            let save = shared.borrow();
+            Ok(save
+                .state_json()
+                .map_err(script_error)?
+                .get("otherCountableResources")
+                .and_then(|r| r.get(resource_id))
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0))
+        },
+    );
+
+    let shared = save.clone();
+    engine.register_fn(
+        "set_resource",
+        move |resource_id: &str, value: INT| -> ScriptResult<()> {
+            set_resource(&mut shared.borrow_mut(), resource_id, value).map_err(script_error)
+        },
+    );
+
+    let shared = save.clone();
+    engine.register_fn(
+        "get_titan",
+        move |titan_id: &str| -> ScriptResult<Dynamic> {
+            let save = shared.borrow();
+            Ok(save
+                .state_json()
+                .map_err(script_error)?
+                .get("openedTitans")
+                .and_then(|t| t.get(titan_id))
+                .and_then(|t| t.get("Item2"))
+                .and_then(|v| v.as_i64())
+                .map_or(Dynamic::UNIT, Dynamic::from))
+        },
+    );
+
+    // Setting a titan that is not yet in `openedTitans` unlocks it.
+    let shared = save.clone();
+    engine.register_fn(
+        "set_titan",
+        move |titan_id: &str, value: INT| -> ScriptResult<()> {
+            let mut save = shared.borrow_mut();
+            let unlocked = save
+                .state_json()
+                .map_err(script_error)?
+                .get("openedTitans")
+                .is_some_and(|t| t.get(titan_id).is_some());
+            if unlocked {
+                set_titan(&mut save, titan_id, value, None).map_err(script_error)
+            } else {
+                insert_titan(&mut save, titan_id, 0, value).map_err(script_error)
+            }
+        },
+    );
+}
+
+fn register_person(engine: &mut Engine) {
+    engine.register_type_with_name::<PersonRef>("Person");
+
+    engine.register_get("id", |p: &mut PersonRef| {
+        p.with(|person| person.get("id").map(json_id_string).unwrap_or_default())
+    });
+    engine.register_get("studio", |p: &mut PersonRef| {
+        p.with(|person| {
+            person
+                .get("studioId")
+                .and_then(|s| s.as_str())
+                .map_or(Dynamic::UNIT, |s| Dynamic::from(s.to_string()))
+        })
+    });
+    engine.register_get("age", |p: &mut PersonRef| {
+        let today = p.today;
+        p.with(|person| {
+            person
+                .get("birthDate")
+                .and_then(|b| b.as_str())
+                .and_then(|b| age_on(b, today))
+                .map_or(Dynamic::UNIT, Dynamic::from)
+        })
+    });
+
+    engine.register_fn("has_profession", |p: &mut PersonRef, profession: &str| {
+        p.with(|person| has_profession(person, profession))
+    });
+    engine.register_fn("skill", |p: &mut PersonRef, profession: &str| {
+        p.with(|person| profession_skill(person, profession).map_or(Dynamic::UNIT, Dynamic::from))
+    });
+    engine.register_fn(
+        "set_skill",
+        |p: &mut PersonRef, profession: &str, skill: FLOAT| -> ScriptResult<()> {
+            if !(MIN_SKILL..=MAX_SKILL).contains(&skill) {
+                return Err(format!(
+                    "Skill {} is out of range ({} to {})",
+                    skill, MIN_SKILL, MAX_SKILL
+                )
+                .into());
+            }
+            p.with(|person| match person.get_mut("professions") {
+                Some(professions) if professions.get(profession).is_some() => {
+                    set_number(professions, profession, skill, NumberFormat::Float);
+                    Ok(())
+                }
+                _ => Err(format!("Person is not a {}", profession).into()),
+            })?
+        },
+    );
+
+    engine.register_fn("has_trait", |p: &mut PersonRef, label: &str| {
+        p.with(|person| {
+            person
+                .get("labels")
+                .and_then(|l| l.as_array())
+                .is_some_and(|arr| arr.iter().any(|t| t.as_str() == Some(label)))
+        })
+    });
+    engine.register_fn("add_trait", |p: &mut PersonRef, label: &str| {
+        p.update(PersonUpdate {
+            add_trait: Some(label.to_string()),
+            ..Default::default()
+        })
+    });
+    engine.register_fn("remove_trait", |p: &mut PersonRef, label: &str| {
+        p.update(PersonUpdate {
+            remove_trait: Some(label.to_string()),
+            ..Default::default()
+        })
+    });
+
+    engine.register_fn("white_tag", |p: &mut PersonRef, tag_id: &str| {
+        p.with(|person| {
+            person
+                .get("whiteTagsNEW")
+                .and_then(|w| w.get(tag_id))
+                .map_or(Dynamic::UNIT, |tag| Dynamic::from(white_tag_value(tag)))
+        })
+    });
+    engine.register_fn(
+        "set_white_tag",
+        |p: &mut PersonRef, tag_id: &str, value: FLOAT| {
+            p.with(|person| upsert_white_tag(person, tag_id, value))
+        },
+    );
+
+    engine.register_indexer_get(|p: &mut PersonRef, field: &str| -> ScriptResult<Dynamic> {
+        p.with(|person| to_dynamic(person.get(field)))?
+    });
+    engine.register_indexer_set(
+        |p: &mut PersonRef, field: &str, value: Dynamic| -> ScriptResult<()> {
+            p.with(|person| write_field(person, field, value))?
+        },
+    );
+}
+
+fn script_error(e: AppError) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+fn to_dynamic(value: Option<&Value>) -> ScriptResult<Dynamic> {
+    value.map_or(Ok(Dynamic::UNIT), rhai::serde::to_dynamic)
+}
+
+/// Writes a script value into a JSON object field. Numbers keep the encoding the
+/// field already uses in the save.
+fn write_field(target: &mut Value, field: &str, value: Dynamic) -> ScriptResult<()> {
+    if !target.is_object() {
+        return Err("Target is not an object".into());
+    }
+    if let Ok(number) = value.as_int() {
+        set_number(target, field, number as f64, NumberFormat::Integer);
+    } else if let Ok(number) = value.as_float() {
+        set_number(target, field, number, NumberFormat::Float);
+    } else {
+        target[field] = rhai::serde::from_dynamic(&value)?;
+    }
+    Ok(())
+}
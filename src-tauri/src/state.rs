@@ -1,48 +1,391 @@
+use notify::RecommendedWatcher;
 use serde_json::Value;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{
+    Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
 
+use crate::error::{AppError, AppResult};
+use crate::models::OpenSave;
+use crate::utils::character_index::CharacterIndex;
+use crate::utils::file_watch::{changed_on_disk, modified_time, watch_file, FileStamp};
 use crate::utils::paths;
+use crate::utils::save_file::encode_save_file;
+use crate::utils::{restore_number_formats, SaveDataExt};
 
-const ERR_NO_SAVE_LOADED: &str = "No save file loaded";
-const ERR_GAME_NOT_FOUND: &str = "Game installation not found. Please click 'Browse for Game Folder' and select your Hollywood Animal installation directory.";
+const ERR_NOTHING_TO_UNDO: &str = "Nothing to undo";
 
+const MAX_CHECKPOINTS: usize = 10;
+
+/// A copy of the save taken before a bulk edit, so the edit can be undone.
+struct Checkpoint {
+    label: String,
+    data: Arc<Value>,
+}
+
+/// One loaded save together with a version that increases on every change.
+/// `on_disk` is the save as last read from or written to its file, which is the
+/// common ancestor when merging with a version the game wrote since. `index`
+/// belongs to the current version and is built the first time it is needed.
+struct SaveSlot {
+    data: Arc<Value>,
+    on_disk: Arc<Value>,
+    version: u64,
+    index: Arc<OnceLock<CharacterIndex>>,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl SaveSlot {
+    fn replace(&mut self, data: Arc<Value>) -> Arc<Value> {
+        self.touch();
+        std::mem::replace(&mut self.data, data)
+    }
+
+    fn touch(&mut self) {
+        self.version += 1;
+        self.index = Arc::default();
+    }
+}
+
+/// The file a save was loaded from, the stamp of its content as last read or
+/// written by the editor, and the watcher reporting changes made by others.
+struct SaveFile {
+    path: String,
+    stamp: Arc<Mutex<FileStamp>>,
+    watcher: Option<RecommendedWatcher>,
+}
+
+struct LoadedSave {
+    order: u64,
+    file: RwLock<SaveFile>,
+    slot: RwLock<SaveSlot>,
+}
+
+/// The open saves by handle. `active` is the most recently loaded save and is
+/// used by commands that are not given a handle.
+#[derive(Default)]
+struct SaveRegistry {
+    saves: HashMap<String, Arc<LoadedSave>>,
+    active: Option<String>,
+    next_handle: u64,
+}
+
+/// An immutable view of a save at a given version. Holding one does not block
+/// edits; the next edit copies the save instead of mutating the shared one.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub handle: String,
+    pub version: u64,
+    pub data: Arc<Value>,
+    pub on_disk: Arc<Value>,
+    index: Arc<OnceLock<CharacterIndex>>,
+}
+
+impl Snapshot {
+    /// The character index of this version, built on first use.
+    pub fn characters_index(&self) -> AppResult<&CharacterIndex> {
+        let characters = self.data.characters()?;
+        Ok(self.index.get_or_init(|| CharacterIndex::build(characters)))
+    }
+
+    pub fn character(&self, id: &str) -> AppResult<&Value> {
+        let position = self
+            .characters_index()?
+            .position(id)
+            .ok_or_else(|| AppError::not_found("Person", id))?;
+        Ok(&self.data.characters()?[position])
+    }
+}
+
+/// Shared backend state. Locks are held only for as long as it takes to swap or
+/// clone an `Arc`, and a lock poisoned by a panicking command is recovered rather
+/// than failing every later command.
 #[derive(Default)]
 pub struct AppState {
-    save_data: Mutex<Option<Value>>,
-    game_path: Mutex<Option<String>>,
+    saves: RwLock<SaveRegistry>,
+    game_path: RwLock<Option<String>>,
 }
 
 impl AppState {
-    pub fn with_save_data<T, F>(&self, f: F) -> Result<T, String>
+    /// Runs `f` against a snapshot of the save without holding any lock.
+    pub fn with_save_data<T, F>(&self, handle: Option<&str>, f: F) -> AppResult<T>
     where
-        F: FnOnce(&Value) -> Result<T, String>,
+        F: FnOnce(&Value) -> AppResult<T>,
     {
-        let guard = self.save_data.lock().unwrap();
-        f(guard.as_ref().ok_or(ERR_NO_SAVE_LOADED)?)
+        f(&self.snapshot(handle)?.data)
     }
 
-    pub fn with_save_data_mut<T, F>(&self, f: F) -> Result<T, String>
+    /// Runs `f` on the save while holding it exclusively. The save is edited in
+    /// place and only copied first while a snapshot of it is still alive, so `f`
+    /// must check everything that can fail before it changes anything; an error
+    /// is taken to mean the save was left as it was.
+    pub fn with_save_data_mut<T, F>(&self, handle: Option<&str>, f: F) -> AppResult<T>
     where
-        F: FnOnce(&mut Value) -> Result<T, String>,
+        F: FnOnce(&mut Value) -> AppResult<T>,
     {
-        let mut guard = self.save_data.lock().unwrap();
-        f(guard.as_mut().ok_or(ERR_NO_SAVE_LOADED)?)
+        let (_, save) = self.resolve(handle)?;
+        let mut slot = write(&save.slot);
+        let result = f(Arc::make_mut(&mut slot.data))?;
+        slot.touch();
+        Ok(result)
     }
 
-    pub fn set_save_data(&self, data: Value) {
-        *self.save_data.lock().unwrap() = Some(data);
+    pub fn snapshot(&self, handle: Option<&str>) -> AppResult<Snapshot> {
+        let (handle, save) = self.resolve(handle)?;
+        let slot = read(&save.slot);
+        Ok(Snapshot {
+            handle,
+            version: slot.version,
+            data: slot.data.clone(),
+            on_disk: slot.on_disk.clone(),
+            index: slot.index.clone(),
+        })
     }
 
-    pub fn ensure_game_path(&self) -> Result<String, String> {
-        let mut guard = self.game_path.lock().unwrap();
-        if guard.is_none() {
-            *guard = paths::find_game_path();
+    /// Stores a freshly read save and its character index and makes it the active
+    /// one. Loading a path that is already open replaces that save and keeps its
+    /// handle.
+    pub fn load_save(
+        &self,
+        path: String,
+        data: Value,
+        index: CharacterIndex,
+        stamp: FileStamp,
+    ) -> String {
+        let index = Arc::new(OnceLock::from(index));
+        let mut registry = write(&self.saves);
+        let existing = registry
+            .saves
+            .iter()
+            .find(|(_, save)| paths::same_path(&read(&save.file).path, &path))
+            .map(|(handle, save)| (handle.clone(), save.clone()));
+
+        let handle = match existing {
+            Some((handle, save)) => {
+                *lock(&read(&save.file).stamp) = stamp;
+                let data = Arc::new(data);
+                let mut slot = write(&save.slot);
+                slot.replace(data.clone());
+                slot.on_disk = data;
+                slot.index = index;
+                slot.checkpoints.clear();
+                handle
+            }
+            None => {
+                registry.next_handle += 1;
+                let data = Arc::new(data);
+                let handle = format!("save-{}", registry.next_handle);
+                let save = LoadedSave {
+                    order: registry.next_handle,
+                    file: RwLock::new(SaveFile {
+                        path,
+                        stamp: Arc::new(Mutex::new(stamp)),
+                        watcher: None,
+                    }),
+                    slot: RwLock::new(SaveSlot {
+                        on_disk: data.clone(),
+                        data,
+                        version: 0,
+                        index,
+                        checkpoints: Vec::new(),
+                    }),
+                };
+                registry.saves.insert(handle.clone(), Arc::new(save));
+                handle
+            }
+        };
+        registry.active = Some(handle.clone());
+        handle
+    }
+
+    pub fn close_save(&self, handle: &str) -> AppResult<()> {
+        let mut registry = write(&self.saves);
+        registry
+            .saves
+            .remove(handle)
+            .ok_or_else(|| AppError::not_found("Save", handle))?;
+        if registry.active.as_deref() == Some(handle) {
+            registry.active = registry
+                .saves
+                .iter()
+                .max_by_key(|(_, save)| save.order)
+                .map(|(handle, _)| handle.clone());
+        }
+        Ok(())
+    }
+
+    pub fn list_saves(&self) -> Vec<OpenSave> {
+        let registry = read(&self.saves);
+        let mut saves: Vec<_> = registry.saves.iter().collect();
+        saves.sort_by_key(|(_, save)| save.order);
+        saves
+            .into_iter()
+            .map(|(handle, save)| OpenSave {
+                handle: handle.clone(),
+                path: read(&save.file).path.clone(),
+                active: registry.active.as_ref() == Some(handle),
+            })
+            .collect()
+    }
+
+    /// Starts watching the file of a save unless it is already watched. `on_change`
+    /// receives the path whenever another program writes a different version.
+    pub fn watch_save<F>(&self, handle: &str, on_change: F) -> AppResult<()>
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        let (_, save) = self.resolve(Some(handle))?;
+        let mut file = write(&save.file);
+        if file.watcher.is_some() {
+            return Ok(());
+        }
+
+        let path = file.path.clone();
+        let watched = path.clone();
+        let watcher = watch_file(Path::new(&path), file.stamp.clone(), move || {
+            on_change(&watched)
+        })
+        .map_err(|e| AppError::Invalid(format!("Failed to watch {}: {}", path, e)))?;
+        file.watcher = Some(watcher);
+        Ok(())
+    }
+
+    /// Writes a save to `path` and returns its handle. Overwriting the file the
+    /// save came from fails if another program changed it since, unless `force`
+    /// is set. Writing anywhere else makes that file the save's file, and the
+    /// returned flag tells the caller to watch it.
+    pub fn write_save(
+        &self,
+        handle: Option<&str>,
+        path: &str,
+        force: bool,
+    ) -> AppResult<(String, bool)> {
+        let (handle, save) = self.resolve(handle)?;
+        let data = {
+            let slot = read(&save.slot);
+            let mut data = (*slot.data).clone();
+            restore_number_formats(&mut data, &slot.on_disk);
+            Arc::new(data)
+        };
+        let mut file = write(&save.file);
+
+        let rebound = !paths::same_path(&file.path, path);
+        if !rebound && !force && changed_on_disk(Path::new(path), &lock(&file.stamp))? {
+            return Err(AppError::ModifiedOnDisk {
+                path: path.to_string(),
+            });
+        }
+
+        let content = encode_save_file(&data)?;
+        let stamp = FileStamp::of(&content, None);
+        if rebound {
+            fs::write(path, &content)?;
+            *file = SaveFile {
+                path: path.to_string(),
+                stamp: Arc::new(Mutex::new(FileStamp {
+                    modified: modified_time(Path::new(path)),
+                    ..stamp
+                })),
+                watcher: None,
+            };
+        } else {
+            // The watcher compares against the recorded stamp, so the new content is
+            // recorded before the write and the old stamp put back if the write fails.
+            let previous = std::mem::replace(&mut *lock(&file.stamp), stamp);
+            if let Err(e) = fs::write(path, &content) {
+                *lock(&file.stamp) = previous;
+                return Err(e.into());
+            }
+            lock(&file.stamp).modified = modified_time(Path::new(path));
         }
-        guard.clone().ok_or_else(|| ERR_GAME_NOT_FOUND.to_string())
+        write(&save.slot).on_disk = data;
+        Ok((handle, rebound))
+    }
+
+    pub fn save_path(&self, handle: Option<&str>) -> AppResult<String> {
+        let (_, save) = self.resolve(handle)?;
+        let path = read(&save.file).path.clone();
+        Ok(path)
+    }
+
+    /// Commits the result of merging with the file on disk. Besides replacing the
+    /// save like [`Self::commit_with_checkpoint`], `disk` and its `stamp` become
+    /// the new common ancestor, so saving no longer reports the file as changed.
+    pub fn commit_merge(
+        &self,
+        label: String,
+        base: &Snapshot,
+        merged: Value,
+        disk: Value,
+        stamp: FileStamp,
+    ) -> AppResult<()> {
+        let (_, save) = self.resolve(Some(&base.handle))?;
+        let file = write(&save.file);
+        self.commit_with_checkpoint(label, base, merged)?;
+        write(&save.slot).on_disk = Arc::new(disk);
+        *lock(&file.stamp) = stamp;
+        Ok(())
+    }
+
+    /// Replaces the save `base` was taken from with `data`, keeping the previous
+    /// save as an undo checkpoint. Only the most recent checkpoints are retained.
+    /// `data` must have been derived from `base`, so an edit made in the meantime
+    /// is never silently overwritten.
+    pub fn commit_with_checkpoint(
+        &self,
+        label: String,
+        base: &Snapshot,
+        data: Value,
+    ) -> AppResult<()> {
+        let (_, save) = self.resolve(Some(&base.handle))?;
+        let mut slot = write(&save.slot);
+        if slot.version != base.version {
+            return Err(AppError::Invalid(format!(
+                "The save changed while the {} was running, please try again",
+                label
+            )));
+        }
+
+        let previous = slot.replace(Arc::new(data));
+        slot.checkpoints.push(Checkpoint {
+            label,
+            data: previous,
+        });
+        if slot.checkpoints.len() > MAX_CHECKPOINTS {
+            slot.checkpoints.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Restores the most recent checkpoint and returns its label.
+    pub fn undo_checkpoint(&self, handle: Option<&str>) -> AppResult<String> {
+        let (_, save) = self.resolve(handle)?;
+        let mut slot = write(&save.slot);
+        let checkpoint = slot
+            .checkpoints
+            .pop()
+            .ok_or_else(|| AppError::Invalid(ERR_NOTHING_TO_UNDO.to_string()))?;
+        slot.replace(checkpoint.data);
+        Ok(checkpoint.label)
+    }
+
+    pub fn checkpoint_labels(&self, handle: Option<&str>) -> AppResult<Vec<String>> {
+        let (_, save) = self.resolve(handle)?;
+        let slot = read(&save.slot);
+        Ok(slot.checkpoints.iter().map(|c| c.label.clone()).collect())
+    }
+
+    pub fn ensure_game_path(&self) -> AppResult<String> {
+        self.get_game_path().ok_or(AppError::GameNotFound)
     }
 
     pub fn get_game_path(&self) -> Option<String> {
-        let mut guard = self.game_path.lock().unwrap();
+        if let Some(path) = read(&self.game_path).clone() {
+            return Some(path);
+        }
+        let mut guard = write(&self.game_path);
         if guard.is_none() {
             *guard = paths::find_game_path();
         }
@@ -50,6 +393,161 @@ impl AppState {
     }
 
     pub fn set_game_path(&self, path: String) {
-        *self.game_path.lock().unwrap() = Some(path);
+        *write(&self.game_path) = Some(path);
+    }
+
+    fn resolve(&self, handle: Option<&str>) -> AppResult<(String, Arc<LoadedSave>)> {
+        let registry = read(&self.saves);
+        let handle = match handle {
+            Some(handle) => handle.to_string(),
+            None => registry.active.clone().ok_or(AppError::NoSaveLoaded)?,
+        };
+        let save = registry
+            .saves
+            .get(&handle)
+            .cloned()
+            .ok_or_else(|| AppError::not_found("Save", &handle))?;
+        Ok((handle, save))
+    }
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| {
+        lock.clear_poison();
+        PoisonError::into_inner(e)
+    })
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| {
+        mutex.clear_poison();
+        PoisonError::into_inner(e)
+    })
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| {
+        lock.clear_poison();
+        PoisonError::into_inner(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn load(state: &AppState, characters: Value) -> String {
+        let data = json!({ "stateJson": { "characters": characters } });
+        let index = CharacterIndex::build(data.characters().unwrap());
+        state.load_save(
+            "test-save.json".to_string(),
+            data,
+            index,
+            FileStamp::of(b"", None),
+        )
+    }
+
+    #[test]
+    fn index_is_rebuilt_after_an_edit() {
+        let state = AppState::default();
+        let handle = load(
+            &state,
+            json!([
+                { "id": 1, "professions": { "CptHR": "0.5" } },
+                { "id": 2, "professions": { "Actor": "0.5" } }
+            ]),
+        );
+        let before = state.snapshot(Some(&handle)).unwrap();
+        assert_eq!(
+            before
+                .characters_index()
+                .unwrap()
+                .with_profession("Executive"),
+            [0]
+        );
+
+        state
+            .with_save_data_mut(Some(&handle), |data| {
+                let characters = data.characters_mut()?;
+                characters[0]["professions"] = json!({ "LieutScript": "0.5" });
+                characters.push(json!({ "id": 3, "professions": { "CptPR": "0.5" } }));
+                Ok(())
+            })
+            .unwrap();
+
+        let after = state.snapshot(Some(&handle)).unwrap();
+        let index = after.characters_index().unwrap();
+        assert_eq!(index.with_profession("Executive"), [2]);
+        assert_eq!(index.with_profession("DepartmentHead"), [0]);
+        assert_eq!(index.position("3"), Some(2));
+        assert_eq!(after.character("3").unwrap()["id"], json!(3));
+
+        // An older snapshot keeps the index of its own version.
+        assert_eq!(
+            before
+                .characters_index()
+                .unwrap()
+                .with_profession("Executive"),
+            [0]
+        );
+    }
+
+    #[test]
+    fn failed_edit_keeps_the_version_and_index() {
+        let state = AppState::default();
+        let handle = load(
+            &state,
+            json!([{ "id": 1, "professions": { "CptHR": "0.5" } }]),
+        );
+        let before = state.snapshot(Some(&handle)).unwrap();
+        let index = Arc::as_ptr(&before.index);
+
+        let result: AppResult<()> = state.with_save_data_mut(Some(&handle), |_| {
+            Err(AppError::Invalid("edit failed".to_string()))
+        });
+        assert!(result.is_err());
+
+        let after = state.snapshot(Some(&handle)).unwrap();
+        assert_eq!(after.version, before.version);
+        assert_eq!(after.data, before.data);
+        assert_eq!(Arc::as_ptr(&after.index), index);
+    }
+
+    #[test]
+    fn edit_copies_the_save_only_while_a_snapshot_is_alive() {
+        let state = AppState::default();
+        let handle = load(&state, json!([{ "id": 1, "mood": 0.5 }]));
+        let set_mood = |mood: f64| {
+            state
+                .with_save_data_mut(Some(&handle), |data| {
+                    data.character_mut("1")?["mood"] = json!(mood);
+                    Ok(())
+                })
+                .unwrap()
+        };
+
+        // The first edit copies: the loaded save is shared with `on_disk`.
+        set_mood(0.6);
+        let snapshot = state.snapshot(Some(&handle)).unwrap();
+        let shared = Arc::as_ptr(&snapshot.data);
+
+        set_mood(0.7);
+        assert_eq!(
+            snapshot.data.pointer("/stateJson/characters/0/mood"),
+            Some(&json!(0.6))
+        );
+        let copied = state.snapshot(Some(&handle)).unwrap().data;
+        assert_ne!(Arc::as_ptr(&copied), shared);
+
+        let in_place = Arc::as_ptr(&copied);
+        drop((snapshot, copied));
+        set_mood(0.8);
+        let after = state.snapshot(Some(&handle)).unwrap();
+        assert_eq!(Arc::as_ptr(&after.data), in_place);
+        assert_eq!(
+            after.data.pointer("/stateJson/characters/0/mood"),
+            Some(&json!(0.8))
+        );
     }
 }
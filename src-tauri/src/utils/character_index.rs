@@ -0,0 +1,148 @@
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::json_id_string;
+use crate::models::has_profession;
+
+/// Professions `has_profession` matches through any of several keys.
+const PROFESSION_GROUPS: [&str; 2] = ["Executive", "DepartmentHead"];
+
+/// Positions in `stateJson.characters` by character id and by profession, so
+/// lookups don't scan every character. An index belongs to one version of the
+/// save and is rebuilt after edits.
+#[derive(Debug, Default)]
+pub struct CharacterIndex {
+    by_id: HashMap<String, usize>,
+    by_profession: HashMap<String, Vec<usize>>,
+}
+
+impl CharacterIndex {
+    pub fn build(characters: &[Value]) -> Self {
+        let mut index = Self::default();
+        for (position, character) in characters.iter().enumerate() {
+            if let Some(id) = character.get("id") {
+                index.by_id.entry(json_id_string(id)).or_insert(position);
+            }
+
+            let professions = character
+                .get("professions")
+                .and_then(|p| p.as_object())
+                .into_iter()
+                .flat_map(|p| p.keys().map(String::as_str));
+            let groups = PROFESSION_GROUPS
+                .into_iter()
+                .filter(|group| has_profession(character, group));
+            for profession in professions.chain(groups) {
+                index
+                    .by_profession
+                    .entry(profession.to_string())
+                    .or_default()
+                    .push(position);
+            }
+        }
+        index
+    }
+
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    /// Positions of the characters `has_profession` would match, in save order.
+    pub fn with_profession(&self, profession: &str) -> &[usize] {
+        self.by_profession
+            .get(profession)
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Some of the characters of a save, serialized straight from the shared save
+/// instead of being copied into a response first.
+pub struct CharacterList {
+    data: Arc<Value>,
+    positions: Vec<usize>,
+}
+
+impl CharacterList {
+    pub fn new(data: Arc<Value>, positions: Vec<usize>) -> Self {
+        Self { data, positions }
+    }
+}
+
+impl Serialize for CharacterList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let characters = self
+            .data
+            .pointer("/stateJson/characters")
+            .and_then(|c| c.as_array())
+            .map_or(&[][..], Vec::as_slice);
+
+        let mut seq = serializer.serialize_seq(Some(self.positions.len()))?;
+        for character in self.positions.iter().filter_map(|&p| characters.get(p)) {
+            seq.serialize_element(character)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn characters() -> Vec<Value> {
+        vec![
+            json!({ "id": 1, "professions": { "Actor": "0.5" } }),
+            json!({ "id": "2", "professions": { "CptHR": "0.5" } }),
+            json!({ "id": 3, "professions": { "LieutScript": "0.5" } }),
+            json!({ "id": 4, "professions": { "Actor": "0.5", "LieutScript": "0.5" } }),
+            json!({ "id": 5, "professions": {} }),
+            json!({ "id": 6 }),
+        ]
+    }
+
+    #[test]
+    fn profession_positions_match_has_profession() {
+        let characters = characters();
+        let index = CharacterIndex::build(&characters);
+
+        for profession in [
+            "Actor",
+            "Director",
+            "CptHR",
+            "LieutScript",
+            "Executive",
+            "DepartmentHead",
+        ] {
+            let expected: Vec<usize> = (0..characters.len())
+                .filter(|&p| has_profession(&characters[p], profession))
+                .collect();
+            assert_eq!(
+                index.with_profession(profession),
+                expected,
+                "{}",
+                profession
+            );
+        }
+    }
+
+    #[test]
+    fn groups_include_every_role_in_them() {
+        let index = CharacterIndex::build(&characters());
+
+        assert_eq!(index.with_profession("Executive"), [1]);
+        assert_eq!(index.with_profession("DepartmentHead"), [2, 3]);
+        assert_eq!(index.with_profession("Actor"), [0, 3]);
+    }
+
+    #[test]
+    fn positions_by_id_accept_numeric_and_string_ids() {
+        let index = CharacterIndex::build(&characters());
+
+        assert_eq!(index.position("1"), Some(0));
+        assert_eq!(index.position("2"), Some(1));
+        assert_eq!(index.position("6"), Some(5));
+        assert_eq!(index.position("7"), None);
+    }
+}
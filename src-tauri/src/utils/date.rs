@@ -1,15 +1,32 @@
+use chrono::NaiveDate;
+
 pub const GAME_START_YEAR: i32 = 1929;
 pub const DEFAULT_TIME_PASSED: &str = "0.00:00:00";
 
-pub fn calculate_current_date(time_passed: &str) -> String {
+pub fn current_game_date(time_passed: &str) -> NaiveDate {
     let days: i64 = time_passed
         .split('.')
         .next()
         .and_then(|s| s.parse().ok())
         .unwrap_or(0);
 
-    let start = chrono::NaiveDate::from_ymd_opt(GAME_START_YEAR, 1, 1).unwrap();
-    (start + chrono::Duration::days(days))
+    let start = NaiveDate::from_ymd_opt(GAME_START_YEAR, 1, 1).unwrap();
+    start + chrono::Duration::days(days)
+}
+
+pub fn calculate_current_date(time_passed: &str) -> String {
+    current_game_date(time_passed)
         .format("%B %d, %Y")
         .to_string()
 }
+
+/// Parses the date part of a save timestamp such as `1929-01-01T00:00:00`.
+pub fn parse_save_date(timestamp: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(timestamp.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Computes an age from a character `birthDate`, which the save stores as `DD-MM-YYYY`.
+pub fn age_on(birth_date: &str, today: NaiveDate) -> Option<i64> {
+    let birth = NaiveDate::parse_from_str(birth_date, "%d-%m-%Y").ok()?;
+    today.years_since(birth).map(i64::from)
+}
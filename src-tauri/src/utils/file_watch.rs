@@ -0,0 +1,102 @@
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use crate::error::{AppError, AppResult};
+
+/// What a save file looked like when it was last read or written by the editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: Option<SystemTime>,
+    pub hash: String,
+}
+
+impl FileStamp {
+    pub fn of(content: &[u8], modified: Option<SystemTime>) -> Self {
+        Self::from_hasher(Sha256::new_with_prefix(content), modified)
+    }
+
+    /// Finishes a hash computed while the file was being read.
+    pub fn from_hasher(hasher: Sha256, modified: Option<SystemTime>) -> Self {
+        let hash = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Self { modified, hash }
+    }
+
+    pub fn read(path: &Path) -> AppResult<Self> {
+        let content = fs::read(path)?;
+        Ok(Self::of(&content, modified_time(path)))
+    }
+}
+
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Whether the file no longer matches `stamp`. The hash is only computed when the
+/// modification time differs, and a file that was deleted counts as changed.
+pub fn changed_on_disk(path: &Path, stamp: &FileStamp) -> AppResult<bool> {
+    if stamp.modified.is_some() && modified_time(path) == stamp.modified {
+        return Ok(false);
+    }
+    match FileStamp::read(path) {
+        Ok(current) => Ok(current.hash != stamp.hash),
+        Err(AppError::Io(e)) if e.kind() == ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
+/// Watches `path` and calls `on_change` once for every new version of the file
+/// that differs from `stamp`. The parent directory is watched rather than the
+/// file itself, so writes that replace the file through a rename are seen too.
+pub fn watch_file(
+    path: &Path,
+    stamp: Arc<Mutex<FileStamp>>,
+    on_change: impl Fn() + Send + 'static,
+) -> notify::Result<RecommendedWatcher> {
+    let target = path.to_path_buf();
+    let mut last_seen: Option<FileStamp> = None;
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if matches!(event.kind, EventKind::Access(_))
+            || !event
+                .paths
+                .iter()
+                .any(|p| p.file_name() == target.file_name())
+        {
+            return;
+        }
+
+        let modified = modified_time(&target);
+        if last_seen
+            .as_ref()
+            .is_some_and(|seen| seen.modified == modified)
+        {
+            return;
+        }
+        let Ok(current) = FileStamp::read(&target) else {
+            return;
+        };
+
+        let recorded = stamp.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let is_new = last_seen
+            .as_ref()
+            .map_or(true, |seen| seen.hash != current.hash);
+        if current.hash != recorded.hash && is_new {
+            on_change();
+        }
+        last_seen = Some(current);
+    })?;
+
+    let dir = path.parent().unwrap_or(path);
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
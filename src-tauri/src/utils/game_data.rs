@@ -0,0 +1,49 @@
+use serde_json::Value;
+use std::fs;
+
+use super::paths;
+use crate::error::{AppError, AppResult};
+
+pub fn load_language_strings(game_path: &str, language_code: &str) -> AppResult<Vec<String>> {
+    let file_path = paths::language_file_path(game_path, language_code);
+
+    let content = fs::read_to_string(&file_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::not_found("Language file", file_path.clone()),
+        _ => AppError::Io(e),
+    })?;
+
+    let data: Value = serde_json::from_str(&content)?;
+
+    data.get("locStrings")
+        .and_then(|s| s.as_array())
+        .ok_or_else(|| AppError::missing("locStrings"))?
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| AppError::Invalid("Invalid locString entry".to_string()))
+        })
+        .collect()
+}
+
+/// Reads a JSON config from the game's data folder. Returns `None` when the file
+/// is missing or unreadable, so callers can fall back to their built-in tables.
+pub fn load_config_file(game_path: &str, file_name: &str) -> Option<Value> {
+    let content = fs::read_to_string(paths::config_file_path(game_path, file_name)).ok()?;
+    serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()
+}
+
+/// Returns the entries of a config file that is either a bare array or an object
+/// wrapping the array under `key`.
+pub fn config_entries<'a>(data: &'a Value, key: &str) -> Option<&'a Vec<Value>> {
+    data.as_array()
+        .or_else(|| data.get(key).and_then(|v| v.as_array()))
+}
+
+/// Reads the id of a config entry, which is either a bare string or an object
+/// with an `id` field.
+pub fn config_entry_id(entry: &Value) -> Option<&str> {
+    entry
+        .as_str()
+        .or_else(|| entry.get("id").and_then(|v| v.as_str()))
+}
@@ -1,32 +1,62 @@
+use chrono::NaiveDate;
 use serde_json::Value;
 
-const ERR_MISSING_STATE_JSON: &str = "Missing stateJson in save file";
-const ERR_MISSING_CHARACTERS: &str = "Missing or invalid characters array";
+use super::date::{current_game_date, DEFAULT_TIME_PASSED};
+use crate::error::{AppError, AppResult};
+
+const STATE_JSON_PATH: &str = "stateJson";
+const CHARACTERS_PATH: &str = "stateJson.characters";
 
 pub trait SaveDataExt {
-    fn state_json(&self) -> Result<&Value, String>;
-    fn characters(&self) -> Result<&Vec<Value>, String>;
-    fn characters_mut(&mut self) -> Result<&mut Vec<Value>, String>;
+    fn state_json(&self) -> AppResult<&Value>;
+    fn game_date(&self) -> AppResult<NaiveDate>;
+    fn characters(&self) -> AppResult<&Vec<Value>>;
+    fn characters_mut(&mut self) -> AppResult<&mut Vec<Value>>;
+    fn character(&self, person_id: &str) -> AppResult<&Value>;
+    fn character_mut(&mut self, person_id: &str) -> AppResult<&mut Value>;
 }
 
 impl SaveDataExt for Value {
-    fn state_json(&self) -> Result<&Value, String> {
-        self.get("stateJson")
-            .ok_or_else(|| ERR_MISSING_STATE_JSON.to_string())
+    fn state_json(&self) -> AppResult<&Value> {
+        self.get(STATE_JSON_PATH)
+            .ok_or_else(|| AppError::missing(STATE_JSON_PATH))
+    }
+
+    fn game_date(&self) -> AppResult<NaiveDate> {
+        let time_passed = self
+            .state_json()?
+            .get("timePassed")
+            .and_then(|t| t.as_str())
+            .unwrap_or(DEFAULT_TIME_PASSED);
+        Ok(current_game_date(time_passed))
     }
 
-    fn characters(&self) -> Result<&Vec<Value>, String> {
+    fn characters(&self) -> AppResult<&Vec<Value>> {
         self.state_json()?
             .get("characters")
             .and_then(|c| c.as_array())
-            .ok_or_else(|| ERR_MISSING_CHARACTERS.to_string())
+            .ok_or_else(|| AppError::missing(CHARACTERS_PATH))
     }
 
-    fn characters_mut(&mut self) -> Result<&mut Vec<Value>, String> {
-        self.get_mut("stateJson")
+    fn characters_mut(&mut self) -> AppResult<&mut Vec<Value>> {
+        self.get_mut(STATE_JSON_PATH)
             .and_then(|s| s.get_mut("characters"))
             .and_then(|c| c.as_array_mut())
-            .ok_or_else(|| ERR_MISSING_CHARACTERS.to_string())
+            .ok_or_else(|| AppError::missing(CHARACTERS_PATH))
+    }
+
+    fn character(&self, person_id: &str) -> AppResult<&Value> {
+        self.characters()?
+            .iter()
+            .find(|c| c.get("id").is_some_and(|id| json_id_matches(id, person_id)))
+            .ok_or_else(|| AppError::not_found("Person", person_id))
+    }
+
+    fn character_mut(&mut self, person_id: &str) -> AppResult<&mut Value> {
+        self.characters_mut()?
+            .iter_mut()
+            .find(|c| c.get("id").is_some_and(|id| json_id_matches(id, person_id)))
+            .ok_or_else(|| AppError::not_found("Person", person_id))
     }
 }
 
@@ -38,7 +68,20 @@ pub fn json_id_matches(value: &Value, target: &str) -> bool {
     }
 }
 
-pub fn get_state_json_mut(data: &mut Value) -> Result<&mut Value, String> {
-    data.get_mut("stateJson")
-        .ok_or_else(|| ERR_MISSING_STATE_JSON.to_string())
+pub fn json_id_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn get_state_json_mut(data: &mut Value) -> AppResult<&mut Value> {
+    data.get_mut(STATE_JSON_PATH)
+        .ok_or_else(|| AppError::missing(STATE_JSON_PATH))
+}
+
+pub fn json_number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
 }
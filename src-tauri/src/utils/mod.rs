@@ -1,6 +1,14 @@
+pub mod character_index;
 pub mod date;
+pub mod file_watch;
+pub mod game_data;
 pub mod json_ext;
+pub mod numeric;
 pub mod paths;
+pub mod save_file;
+pub mod save_merge;
+pub mod save_validator;
 
 pub use date::*;
 pub use json_ext::*;
+pub use numeric::*;
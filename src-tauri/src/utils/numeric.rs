@@ -0,0 +1,196 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::{json_id_string, json_number};
+
+/// How a numeric save field is encoded. The game stores some numbers as JSON
+/// numbers and others as strings with a fixed precision (`"12.000"`), and expects
+/// edits to keep that form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberFormat {
+    Integer,
+    Float,
+    Text { decimals: usize },
+}
+
+impl NumberFormat {
+    pub fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(n) if n.is_f64() => Some(Self::Float),
+            Value::Number(_) => Some(Self::Integer),
+            Value::String(s) if s.trim().parse::<f64>().is_ok() => Some(Self::Text {
+                decimals: s.trim().split_once('.').map_or(0, |(_, frac)| frac.len()),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn encode(self, value: f64) -> Value {
+        match self {
+            Self::Integer if value.fract() == 0.0 => serde_json::json!(value as i64),
+            Self::Integer | Self::Float => serde_json::json!(value),
+            Self::Text { decimals: 0 } if value.fract() != 0.0 => Value::String(value.to_string()),
+            Self::Text { decimals } => Value::String(format!("{:.*}", decimals, value)),
+        }
+    }
+}
+
+/// Writes `value` to `target[key]` in the field's current encoding, or in
+/// `default` when the field is missing or not numeric. The encoding the file had
+/// when it was loaded is put back on write by [`restore_number_formats`].
+pub fn set_number(target: &mut Value, key: &str, value: f64, default: NumberFormat) {
+    let format = target
+        .get(key)
+        .and_then(NumberFormat::of)
+        .unwrap_or(default);
+    target[key] = format.encode(value);
+}
+
+/// Re-encodes every number in `edited` that differs from `original` the way
+/// `original` stores it. `original` is the save as loaded, so edits are written
+/// in the encoding the game used even when they changed it in memory (an integer
+/// field set to 12.5 and then back to 13). Fields `original` lacks keep their
+/// encoding.
+///
+/// Array elements are paired by [`element_key`] when every original element has
+/// a distinct one, so removing or inserting an element doesn't shift the others
+/// onto the wrong original. Arrays without keys are only restored while their
+/// length is unchanged.
+pub fn restore_number_formats(edited: &mut Value, original: &Value) {
+    match (edited, original) {
+        (Value::Object(edited), Value::Object(original)) => {
+            for (key, value) in edited.iter_mut() {
+                if let Some(original) = original.get(key) {
+                    restore_number_formats(value, original);
+                }
+            }
+        }
+        (Value::Array(edited), Value::Array(original)) => {
+            if let Some(by_key) = index_by_key(original) {
+                for value in edited.iter_mut() {
+                    if let Some(original) = element_key(value).and_then(|k| by_key.get(&k)) {
+                        restore_number_formats(value, original);
+                    }
+                }
+            } else if edited.len() == original.len() {
+                for (value, original) in edited.iter_mut().zip(original) {
+                    restore_number_formats(value, original);
+                }
+            }
+        }
+        (edited @ (Value::Number(_) | Value::String(_)), original) if *edited != *original => {
+            if let (Some(format), Some(value)) = (NumberFormat::of(original), json_number(edited)) {
+                *edited = format.encode(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// What identifies an array element across edits: the `id` of characters, movies
+/// and most other records, or `movieId` and `sourceType` for white-tag
+/// `overallValues` entries.
+fn element_key(value: &Value) -> Option<String> {
+    if let Some(id) = value.get("id") {
+        return Some(json_id_string(id));
+    }
+    match (value.get("movieId"), value.get("sourceType")) {
+        (Some(movie), Some(source)) => Some(format!(
+            "{}/{}",
+            json_id_string(movie),
+            json_id_string(source)
+        )),
+        _ => None,
+    }
+}
+
+/// The elements of `items` by key, or `None` if an element has no key or two
+/// share one.
+fn index_by_key(items: &[Value]) -> Option<HashMap<String, &Value>> {
+    let mut by_key = HashMap::with_capacity(items.len());
+    for item in items {
+        if by_key.insert(element_key(item)?, item).is_some() {
+            return None;
+        }
+    }
+    Some(by_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(movie_id: i64, source_type: i64, value: &str) -> Value {
+        json!({ "movieId": movie_id, "sourceType": source_type, "value": value })
+    }
+
+    #[test]
+    fn restores_each_encoding_after_an_edit() {
+        let original = json!({ "budget": 100, "mood": 0.5, "reputation": "12.000" });
+        let mut edited = json!({ "budget": 13.0, "mood": "0.75", "reputation": 13.5 });
+
+        restore_number_formats(&mut edited, &original);
+
+        assert_eq!(
+            edited,
+            json!({ "budget": 13, "mood": 0.75, "reputation": "13.500" })
+        );
+    }
+
+    #[test]
+    fn keeps_unchanged_values_and_new_fields() {
+        let original = json!({ "budget": 100, "name": "Studio" });
+        let mut edited = json!({ "budget": 100, "name": "Studio", "cash": 2.5 });
+
+        restore_number_formats(&mut edited, &original);
+
+        assert_eq!(
+            edited,
+            json!({ "budget": 100, "name": "Studio", "cash": 2.5 })
+        );
+    }
+
+    #[test]
+    fn removed_element_does_not_shift_the_others() {
+        let original = json!([entry(0, 0, "0.5"), entry(7, 2, "1"), entry(9, 2, "0.250")]);
+        let mut edited = json!([entry(0, 0, "0.5"), {
+            "movieId": 9, "sourceType": 2, "value": 0.75
+        }]);
+
+        restore_number_formats(&mut edited, &original);
+
+        assert_eq!(edited, json!([entry(0, 0, "0.5"), entry(9, 2, "0.750")]));
+    }
+
+    #[test]
+    fn inserted_element_does_not_shift_the_others() {
+        let original = json!([{ "id": 1, "mood": "0.50" }, { "id": 2, "mood": 1 }]);
+        let mut edited = json!([
+            { "id": 3, "mood": 0.25 },
+            { "id": 1, "mood": 0.75 },
+            { "id": 2, "mood": 3.0 }
+        ]);
+
+        restore_number_formats(&mut edited, &original);
+
+        assert_eq!(
+            edited,
+            json!([
+                { "id": 3, "mood": 0.25 },
+                { "id": 1, "mood": "0.75" },
+                { "id": 2, "mood": 3 }
+            ])
+        );
+    }
+
+    #[test]
+    fn unkeyed_arrays_are_only_restored_at_the_same_length() {
+        let original = json!({ "same": ["1.0", 2], "shorter": ["1.0", 2] });
+        let mut edited = json!({ "same": [1.5, 3.0], "shorter": [3.0] });
+
+        restore_number_formats(&mut edited, &original);
+
+        assert_eq!(edited, json!({ "same": ["1.5", 3], "shorter": [3.0] }));
+    }
+}
@@ -10,6 +10,10 @@ pub const STEAM_LIBRARIES: &[&str] = &[
 ];
 
 pub const GAME_FOLDER: &str = "Hollywood Animal";
+pub const PRESETS_FOLDER: &str = "presets";
+pub const DATA_SUBPATH: &str = "Hollywood Animal_Data\\StreamingAssets\\Data";
+pub const CONFIGS_FOLDER: &str = "Configs";
+pub const STUDIO_LOGOS_FOLDER: &str = "StudioLogos";
 pub const LOCALIZATION_SUBPATH: &str = "Hollywood Animal_Data\\StreamingAssets\\Data\\Localization";
 
 pub fn find_game_path() -> Option<String> {
@@ -24,6 +28,17 @@ pub fn localization_path(game_path: &str) -> String {
     format!("{}\\{}", game_path, LOCALIZATION_SUBPATH)
 }
 
+pub fn config_file_path(game_path: &str, file_name: &str) -> String {
+    format!(
+        "{}\\{}\\{}\\{}",
+        game_path, DATA_SUBPATH, CONFIGS_FOLDER, file_name
+    )
+}
+
+pub fn studio_logos_path(game_path: &str) -> String {
+    format!("{}\\{}\\{}", game_path, DATA_SUBPATH, STUDIO_LOGOS_FOLDER)
+}
+
 pub fn language_file_path(game_path: &str, language_code: &str) -> String {
     format!(
         "{}\\{}\\CHARACTER_NAMES.json",
@@ -36,3 +51,11 @@ pub fn validate_game_path(path: &str) -> bool {
     let localization = localization_path(path);
     std::path::Path::new(&localization).exists()
 }
+
+/// Whether two paths name the same file. Existing files are compared by their
+/// canonical path, so different spellings of one path match.
+pub fn same_path(a: &str, b: &str) -> bool {
+    let canonical =
+        |p: &str| std::fs::canonicalize(p).unwrap_or_else(|_| std::path::PathBuf::from(p));
+    canonical(a) == canonical(b)
+}
@@ -0,0 +1,71 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::error::AppResult;
+use crate::utils::file_watch::FileStamp;
+
+const UTF8_BOM: &[u8] = "\u{feff}".as_bytes();
+
+/// Late-game saves run to tens of megabytes, so they are read in large chunks.
+const READ_BUFFER_SIZE: usize = 1 << 20;
+
+/// Reads and parses a save file, tolerating the UTF-8 BOM the game writes.
+pub fn read_save_file(path: &str) -> AppResult<Value> {
+    Ok(read_save_file_stamped(path)?.0)
+}
+
+/// Like [`read_save_file`], also returning the stamp of the content that was read.
+/// The file is parsed straight from a buffered reader and hashed on the way, so
+/// its text is never held in memory as a whole.
+pub fn read_save_file_stamped(path: &str) -> AppResult<(Value, FileStamp)> {
+    let file = File::open(path)?;
+    let modified = file.metadata()?.modified().ok();
+    let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, HashingReader::new(file));
+
+    if reader.fill_buf()?.starts_with(UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+    let data = serde_json::from_reader(&mut reader)?;
+    // The parser stops at the end of the JSON value; hash anything after it too.
+    io::copy(&mut reader, &mut io::sink())?;
+
+    let hasher = reader.into_inner().hasher;
+    Ok((data, FileStamp::from_hasher(hasher, modified)))
+}
+
+/// Serializes the save with the leading BOM the game expects.
+pub fn encode_save_file(data: &Value) -> AppResult<Vec<u8>> {
+    let mut content = UTF8_BOM.to_vec();
+    serde_json::to_writer(&mut content, data)?;
+    Ok(content)
+}
+
+/// Writes the save back with the leading BOM the game expects.
+pub fn write_save_file(path: &str, data: &Value) -> AppResult<()> {
+    Ok(fs::write(path, encode_save_file(data)?)?)
+}
+
+/// Feeds everything read through it into a SHA-256 hasher.
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
@@ -0,0 +1,288 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::json_id_string;
+use crate::models::MergeConflict;
+
+const CHARACTERS_POINTER: &str = "/characters";
+
+/// Three-way merges the editor's changes into a save the game wrote since it was
+/// loaded. `base` is the save as loaded, `ours` the edited save and `theirs` the
+/// save now on disk.
+///
+/// Only `stateJson` is merged; everything else is taken from `theirs`. Objects,
+/// such as the studio fields and `competitorStudios`, are merged key by key and
+/// characters by id. A value changed differently on both sides is a conflict: the
+/// merged save keeps the game's value and the conflict is reported with a pointer
+/// relative to `stateJson`, so it can be resolved with a JSON patch.
+pub fn merge_saves(base: &Value, ours: &Value, theirs: &Value) -> (Value, Vec<MergeConflict>) {
+    let mut merger = Merger::default();
+    let merged_state = merger.merge(
+        "",
+        "stateJson",
+        base.get("stateJson"),
+        ours.get("stateJson"),
+        theirs.get("stateJson"),
+    );
+
+    let mut merged = theirs.clone();
+    if let (Some(root), Some(state_json)) = (merged.as_object_mut(), merged_state) {
+        root.insert("stateJson".to_string(), state_json);
+    }
+    (merged, merger.conflicts)
+}
+
+#[derive(Default)]
+struct Merger {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger {
+    fn merge(
+        &mut self,
+        pointer: &str,
+        path: &str,
+        base: Option<&Value>,
+        ours: Option<&Value>,
+        theirs: Option<&Value>,
+    ) -> Option<Value> {
+        if ours == base {
+            return theirs.cloned();
+        }
+        if theirs == base || ours == theirs {
+            return ours.cloned();
+        }
+
+        match (ours, theirs) {
+            (Some(Value::Object(o)), Some(Value::Object(t))) => {
+                let empty = Map::new();
+                let b = base.and_then(Value::as_object).unwrap_or(&empty);
+                Some(Value::Object(self.merge_objects(pointer, path, b, o, t)))
+            }
+            (Some(Value::Array(o)), Some(Value::Array(t))) if pointer == CHARACTERS_POINTER => {
+                let b = base
+                    .and_then(Value::as_array)
+                    .map_or(&[][..], Vec::as_slice);
+                Some(Value::Array(self.merge_by_id(pointer, path, b, o, t)))
+            }
+            _ => {
+                self.conflicts.push(MergeConflict {
+                    path: path.to_string(),
+                    pointer: pointer.to_string(),
+                    base: base.cloned(),
+                    ours: ours.cloned(),
+                    theirs: theirs.cloned(),
+                });
+                theirs.cloned()
+            }
+        }
+    }
+
+    fn merge_objects(
+        &mut self,
+        pointer: &str,
+        path: &str,
+        base: &Map<String, Value>,
+        ours: &Map<String, Value>,
+        theirs: &Map<String, Value>,
+    ) -> Map<String, Value> {
+        let keys = theirs
+            .keys()
+            .chain(ours.keys().filter(|k| !theirs.contains_key(*k)));
+
+        let mut merged = Map::new();
+        for key in keys {
+            let child_pointer = format!("{}/{}", pointer, escape_pointer(key));
+            let child_path = format!("{}.{}", path, key);
+            if let Some(value) = self.merge(
+                &child_pointer,
+                &child_path,
+                base.get(key),
+                ours.get(key),
+                theirs.get(key),
+            ) {
+                merged.insert(key.clone(), value);
+            }
+        }
+        merged
+    }
+
+    /// Merges arrays of objects matched by their `id`. The game's order is kept and
+    /// characters only the editor added are appended.
+    fn merge_by_id(
+        &mut self,
+        pointer: &str,
+        path: &str,
+        base: &[Value],
+        ours: &[Value],
+        theirs: &[Value],
+    ) -> Vec<Value> {
+        let (base_by_id, ours_by_id, theirs_by_id) =
+            (index_by_id(base), index_by_id(ours), index_by_id(theirs));
+
+        let ids = theirs.iter().map(character_id).chain(
+            ours.iter()
+                .map(character_id)
+                .filter(|id| !theirs_by_id.contains_key(id)),
+        );
+
+        let mut merged = Vec::new();
+        for id in ids {
+            let child_pointer = format!("{}/{}", pointer, merged.len());
+            let child_path = format!("{}[id={}]", path, id);
+            if let Some(value) = self.merge(
+                &child_pointer,
+                &child_path,
+                base_by_id.get(&id).copied(),
+                ours_by_id.get(&id).copied(),
+                theirs_by_id.get(&id).copied(),
+            ) {
+                merged.push(value);
+            }
+        }
+        merged
+    }
+}
+
+fn character_id(character: &Value) -> String {
+    character.get("id").map(json_id_string).unwrap_or_default()
+}
+
+fn index_by_id(items: &[Value]) -> HashMap<String, &Value> {
+    items.iter().map(|v| (character_id(v), v)).collect()
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn save(characters: Value, budget: i64) -> Value {
+        json!({
+            "version": 1,
+            "stateJson": { "budget": budget, "characters": characters }
+        })
+    }
+
+    fn character(id: i64, mood: f64) -> Value {
+        json!({ "id": id, "mood": mood })
+    }
+
+    #[test]
+    fn keeps_edits_made_on_one_side_only() {
+        let base = save(json!([character(1, 0.5), character(2, 0.5)]), 100);
+        let ours = save(json!([character(1, 0.9), character(2, 0.5)]), 100);
+        let theirs = save(json!([character(1, 0.5), character(2, 0.1)]), 250);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged,
+            save(json!([character(1, 0.9), character(2, 0.1)]), 250)
+        );
+    }
+
+    #[test]
+    fn takes_everything_outside_state_json_from_disk() {
+        let base = save(json!([]), 100);
+        let mut theirs = base.clone();
+        theirs["version"] = json!(2);
+
+        let (merged, conflicts) = merge_saves(&base, &base, &theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(merged["version"], json!(2));
+    }
+
+    #[test]
+    fn conflicting_scalar_keeps_the_game_value_and_is_reported() {
+        let base = save(json!([]), 100);
+        let ours = save(json!([]), 500);
+        let theirs = save(json!([]), 250);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert_eq!(merged["stateJson"]["budget"], json!(250));
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.path, "stateJson.budget");
+        assert_eq!(conflict.pointer, "/budget");
+        assert_eq!(conflict.base, Some(json!(100)));
+        assert_eq!(conflict.ours, Some(json!(500)));
+        assert_eq!(conflict.theirs, Some(json!(250)));
+    }
+
+    #[test]
+    fn conflicting_character_field_points_at_the_merged_index() {
+        // Character 1 is gone from disk, so character 2 sits at index 0 of the
+        // merged array.
+        let base = save(json!([character(1, 0.5), character(2, 0.5)]), 100);
+        let ours = save(json!([character(1, 0.5), character(2, 0.9)]), 100);
+        let theirs = save(json!([character(2, 0.1)]), 100);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.path, "stateJson.characters[id=2].mood");
+        assert_eq!(conflict.pointer, "/characters/0/mood");
+        assert_eq!(
+            merged["stateJson"].pointer(&conflict.pointer),
+            Some(&json!(0.1))
+        );
+    }
+
+    #[test]
+    fn keeps_characters_added_on_either_side() {
+        let base = save(json!([character(1, 0.5)]), 100);
+        let ours = save(json!([character(1, 0.5), character(3, 0.5)]), 100);
+        let theirs = save(json!([character(1, 0.5), character(2, 0.5)]), 100);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(
+            merged["stateJson"]["characters"],
+            json!([character(1, 0.5), character(2, 0.5), character(3, 0.5)])
+        );
+    }
+
+    #[test]
+    fn drops_characters_removed_on_either_side() {
+        let base = save(json!([character(1, 0.5), character(2, 0.5)]), 100);
+        let ours = save(json!([character(2, 0.5)]), 100);
+        let theirs = save(json!([character(1, 0.5)]), 100);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(merged["stateJson"]["characters"], json!([]));
+    }
+
+    #[test]
+    fn removing_a_character_the_other_side_edited_is_a_conflict() {
+        let base = save(json!([character(1, 0.5), character(2, 0.5)]), 100);
+        let ours = save(json!([character(1, 0.9), character(2, 0.5)]), 100);
+        let theirs = save(json!([character(2, 0.5)]), 100);
+
+        let (merged, conflicts) = merge_saves(&base, &ours, &theirs);
+
+        assert_eq!(
+            merged["stateJson"]["characters"],
+            json!([character(2, 0.5)])
+        );
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        // Characters missing from disk come after the game's, so restoring it
+        // means adding it at the end of the merged array.
+        assert_eq!(conflict.path, "stateJson.characters[id=1]");
+        assert_eq!(conflict.pointer, "/characters/1");
+        assert_eq!(conflict.ours, Some(character(1, 0.9)));
+        assert_eq!(conflict.theirs, None);
+    }
+}
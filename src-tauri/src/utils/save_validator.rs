@@ -0,0 +1,94 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::{json_id_string, json_number};
+
+const NUMERIC_STUDIO_FIELDS: [&str; 4] = ["budget", "cash", "influence", "reputation"];
+const OBJECT_STUDIO_FIELDS: [&str; 4] = [
+    "openedTitans",
+    "otherCountableResources",
+    "timeBonuses",
+    "competitorStudios",
+];
+const MIN_SKILL: f64 = 0.0;
+const MAX_SKILL: f64 = 1.0;
+
+/// Checks the parts of the save the editor and the game rely on and returns a
+/// description of every problem found. An empty list means the save looks sound.
+pub fn validate_save(data: &Value) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let Some(state_json) = data.get("stateJson").filter(|s| s.is_object()) else {
+        issues.push("stateJson is missing or not an object".to_string());
+        return issues;
+    };
+
+    for field in NUMERIC_STUDIO_FIELDS {
+        if let Some(value) = state_json.get(field) {
+            if json_number(value).is_none() {
+                issues.push(format!("stateJson.{} is not a number", field));
+            }
+        }
+    }
+    for field in OBJECT_STUDIO_FIELDS {
+        if let Some(value) = state_json.get(field) {
+            if !value.is_object() {
+                issues.push(format!("stateJson.{} is not an object", field));
+            }
+        }
+    }
+    if let Some(time_passed) = state_json.get("timePassed") {
+        if !time_passed.is_string() {
+            issues.push("stateJson.timePassed is not a string".to_string());
+        }
+    }
+
+    match state_json.get("characters").and_then(|c| c.as_array()) {
+        Some(characters) => validate_characters(characters, &mut issues),
+        None => issues.push("stateJson.characters is missing or not an array".to_string()),
+    }
+
+    issues
+}
+
+fn validate_characters(characters: &[Value], issues: &mut Vec<String>) {
+    let mut seen_ids = HashSet::new();
+
+    for (index, character) in characters.iter().enumerate() {
+        if !character.is_object() {
+            issues.push(format!("characters[{}] is not an object", index));
+            continue;
+        }
+
+        let Some(id) = character.get("id").filter(|id| !id.is_null()) else {
+            issues.push(format!("characters[{}] has no id", index));
+            continue;
+        };
+        let id = json_id_string(id);
+        if !seen_ids.insert(id.clone()) {
+            issues.push(format!("Character id {} is used more than once", id));
+        }
+
+        match character.get("studioId") {
+            None | Some(Value::Null) | Some(Value::String(_)) => {}
+            Some(_) => issues.push(format!("Character {} has an invalid studioId", id)),
+        }
+
+        match character.get("professions") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(professions)) => {
+                for (profession, skill) in professions {
+                    let in_range =
+                        json_number(skill).is_some_and(|s| (MIN_SKILL..=MAX_SKILL).contains(&s));
+                    if !in_range {
+                        issues.push(format!(
+                            "Character {} has an invalid {} skill",
+                            id, profession
+                        ));
+                    }
+                }
+            }
+            Some(_) => issues.push(format!("Character {} has invalid professions", id)),
+        }
+    }
+}
@@ -1,16 +1,18 @@
-import { useState, useCallback, useMemo } from 'react';
+import { useState, useCallback, useEffect, useMemo } from 'react';
 import { RefreshCw } from 'lucide-react';
-import { saveManager, PersonUtils } from '@/lib';
+import { ask } from '@tauri-apps/plugin-dialog';
+import { saveManager, PersonUtils, ErrorUtils } from '@/lib';
 import appBanner from '@/assets/appBanner.png';
 import { ProfessionTab } from '@/components/ProfessionTab';
 import { ErrorBanner } from '@/components/ErrorBanner';
+import { MergeConflictDialog } from '@/components/MergeConflictDialog';
 import { StudioInfoBar } from '@/components/StudioInfoBar';
 import { useAsyncAction } from '@/hooks/useAsyncAction';
 import { useTabState } from '@/hooks/useTabState';
 import { Button } from '@/components/ui/button';
 import { Label } from '@/components/ui/label';
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select';
-import type { SortField, SortOrder, SaveInfo, CompetitorStudio, Person } from '@/lib';
+import type { SortField, SortOrder, SaveInfo, CompetitorStudio, Person, MergeConflict } from '@/lib';
 
 interface UsedPortrait {
   characterName: string;
@@ -75,6 +77,7 @@ export default function App() {
   const [resources, setResources] = useState<Record<string, number>>({});
   const [titans, setTitans] = useState<Record<string, number>>({});
   const [timeBonuses, setTimeBonuses] = useState<Record<string, number>>({});
+  const [timeBonusMax, setTimeBonusMax] = useState<Record<string, number>>({});
   const [competitors, setCompetitors] = useState<CompetitorStudio[]>([]);
   const [selectedLanguage, setSelectedLanguage] = useState<typeof LANGUAGES[number]>('ENG');
   const [fileKey, setFileKey] = useState<string | null>(null);
@@ -86,8 +89,17 @@ export default function App() {
   const [talentPortraits, setTalentPortraits] = useState<Map<string, Map<number, UsedPortrait>>>(new Map());
   const [lieutPortraits, setLieutPortraits] = useState<Map<string, Map<number, UsedPortrait>>>(new Map());
   const [agentPortraits, setAgentPortraits] = useState<Map<string, Map<number, UsedPortrait>>>(new Map());
+  const [fileChangedOnDisk, setFileChangedOnDisk] = useState(false);
+  const [mergeConflicts, setMergeConflicts] = useState<MergeConflict[]>([]);
   const { loading, error, execute, clearError } = useAsyncAction();
 
+  useEffect(() => {
+    const unlisten = saveManager.onSaveFileChanged(() => setFileChangedOnDisk(true));
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
   const handleSortChange = useCallback((field: SortField, order: SortOrder) => {
     setSortField(field);
     setSortOrder(order);
@@ -164,17 +176,21 @@ export default function App() {
     setResources({});
     setTitans({});
     setTimeBonuses({});
+    setTimeBonusMax({});
     setCompetitors([]);
     setFileKey(null);
     setTalentPortraits(new Map());
     setLieutPortraits(new Map());
     setAgentPortraits(new Map());
+    setFileChangedOnDisk(false);
+    setMergeConflicts([]);
   };
 
   const loadSaveData = async (info: SaveInfo, nameStrings: string[]) => {
     const res = await saveManager.getResources();
     const tit = await saveManager.getTitans();
     const tb = await saveManager.getTimeBonuses();
+    const departments = await saveManager.getDepartmentCatalogue();
     const comp = await saveManager.getCompetitors();
     
     const talentPortraitMap = new Map<string, Map<number, UsedPortrait>>();
@@ -221,6 +237,7 @@ export default function App() {
     setResources(res);
     setTitans(tit);
     setTimeBonuses(tb);
+    setTimeBonusMax(Object.fromEntries(departments.entries.map(d => [d.id, d.max_time_bonus])));
     setCompetitors(comp);
     setTalentPortraits(talentPortraitMap);
     setLieutPortraits(lieutPortraitMap);
@@ -246,7 +263,53 @@ export default function App() {
     }
   });
 
-  const handleSaveFile = () => execute(() => saveManager.saveSaveFile());
+  // Reloading the file would drop the merged edits and their undo checkpoint, so
+  // the views are refreshed from the save in memory instead.
+  const refreshSaveData = async () => {
+    const info = await saveManager.getSaveInfo();
+    const nameStrings = await saveManager.getLanguageStrings(selectedLanguage);
+    await loadSaveData(info, nameStrings);
+  };
+
+  const handleSaveFile = () => execute(async () => {
+    try {
+      await saveManager.saveSaveFile();
+    } catch (err) {
+      if (!ErrorUtils.isAppError(err) || err.code !== 'MODIFIED_ON_DISK') throw err;
+      const merge = await ask(
+        'The save file was changed on disk since it was loaded, probably by a game autosave. Merge your edits into the new version?',
+        { title: 'Save file changed', kind: 'warning' }
+      );
+      if (merge) {
+        const report = await saveManager.mergeWithDisk();
+        setFileChangedOnDisk(false);
+        await refreshSaveData();
+        if (report.conflicts.length > 0) {
+          setMergeConflicts(report.conflicts);
+          return;
+        }
+        await saveManager.saveSaveFile();
+        return;
+      }
+      const overwrite = await ask('Overwrite the changed file with your edits instead?', {
+        title: 'Save file changed',
+        kind: 'warning',
+      });
+      if (!overwrite) return;
+      await saveManager.saveSaveFile(true);
+    }
+    setFileChangedOnDisk(false);
+  });
+
+  const handleResolveConflicts = (keepOurs: MergeConflict[]) => execute(async () => {
+    if (keepOurs.length > 0) {
+      await saveManager.keepOurValues(keepOurs);
+      await refreshSaveData();
+    }
+    await saveManager.saveSaveFile();
+    setMergeConflicts([]);
+  });
+
   const handleSaveFileAs = () => execute(() => saveManager.saveSaveFileAs());
 
   const handleStudioUpdate = async (field: 'budget' | 'cash' | 'reputation' | 'influence', value: number) => {
@@ -381,6 +444,19 @@ export default function App() {
 
       {error && <ErrorBanner message={error} onDismiss={clearError} />}
 
+      {fileChangedOnDisk && (
+        <ErrorBanner
+          message="The save file was changed on disk. Refresh to load the new version, or Save to overwrite it."
+          onDismiss={() => setFileChangedOnDisk(false)}
+        />
+      )}
+
+      <MergeConflictDialog
+        conflicts={mergeConflicts}
+        onResolve={handleResolveConflicts}
+        onCancel={() => setMergeConflicts([])}
+      />
+
       {saveInfo && (
         <StudioInfoBar
           currentDate={saveInfo.current_date}
@@ -393,6 +469,7 @@ export default function App() {
           resources={resources}
           titans={titans}
           timeBonuses={timeBonuses}
+          timeBonusMax={timeBonusMax}
           competitors={competitors}
           onStudioUpdate={handleStudioUpdate}
           onResourceUpdate={handleResourceUpdate}
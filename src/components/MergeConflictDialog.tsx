@@ -0,0 +1,91 @@
+import { useState, useEffect } from 'react';
+import { Button } from '@/components/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import type { MergeConflict } from '@/lib';
+
+interface MergeConflictDialogProps {
+  conflicts: MergeConflict[];
+  onResolve: (keepOurs: MergeConflict[]) => void;
+  onCancel: () => void;
+}
+
+function formatValue(value: unknown): string {
+  if (value === null || value === undefined) return '(removed)';
+  const text = JSON.stringify(value);
+  return text.length > 80 ? `${text.slice(0, 77)}...` : text;
+}
+
+export function MergeConflictDialog({ conflicts, onResolve, onCancel }: MergeConflictDialogProps) {
+  const [keepOurs, setKeepOurs] = useState<Set<string>>(new Set());
+
+  useEffect(() => {
+    setKeepOurs(new Set());
+  }, [conflicts]);
+
+  const choose = (pointer: string, ours: boolean) => {
+    setKeepOurs(prev => {
+      const next = new Set(prev);
+      if (ours) next.add(pointer);
+      else next.delete(pointer);
+      return next;
+    });
+  };
+
+  return (
+    <Dialog open={conflicts.length > 0} onOpenChange={open => !open && onCancel()}>
+      <DialogContent className="max-w-3xl max-h-[85vh] flex flex-col">
+        <DialogHeader>
+          <DialogTitle>Resolve merge conflicts</DialogTitle>
+          <DialogDescription>
+            The game changed these values too. Pick which version to keep; nothing is saved until you confirm.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="flex-1 overflow-y-auto space-y-2 pr-2">
+          {conflicts.map(conflict => {
+            const ours = keepOurs.has(conflict.pointer);
+            return (
+              <div key={conflict.pointer} className="rounded-lg border p-3 space-y-2">
+                <div className="text-sm font-medium break-all">{conflict.path}</div>
+                <div className="grid grid-cols-2 gap-2">
+                  <Button
+                    variant={ours ? 'default' : 'outline'}
+                    size="sm"
+                    className="justify-start h-auto py-1.5 font-mono text-xs whitespace-normal break-all text-left"
+                    onClick={() => choose(conflict.pointer, true)}
+                  >
+                    Mine: {formatValue(conflict.ours)}
+                  </Button>
+                  <Button
+                    variant={ours ? 'outline' : 'default'}
+                    size="sm"
+                    className="justify-start h-auto py-1.5 font-mono text-xs whitespace-normal break-all text-left"
+                    onClick={() => choose(conflict.pointer, false)}
+                  >
+                    Game's: {formatValue(conflict.theirs)}
+                  </Button>
+                </div>
+              </div>
+            );
+          })}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={onCancel}>
+            Don't save yet
+          </Button>
+          <Button onClick={() => onResolve(conflicts.filter(c => keepOurs.has(c.pointer)))}>
+            Save
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
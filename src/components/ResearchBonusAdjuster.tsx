@@ -6,16 +6,17 @@ interface ResearchBonusAdjusterProps {
   icon: string;
   alt: string;
   value: number;
+  max: number;
   onChange: (value: number) => void;
 }
 
-export function ResearchBonusAdjuster({ icon, alt, value, onChange }: ResearchBonusAdjusterProps) {
+export function ResearchBonusAdjuster({ icon, alt, value, max, onChange }: ResearchBonusAdjusterProps) {
   const { startHold, stopHold } = useHoldAcceleration(
     value,
     onChange,
     { initialStep: 1, acceleratedStep: 5, snapToGrid: true, gridSize: 5 },
     0,
-    5
+    max
   );
 
   return (
@@ -28,7 +29,7 @@ export function ResearchBonusAdjuster({ icon, alt, value, onChange }: ResearchBo
         onIncreaseMouseDown={() => startHold(1)}
         onIncreaseMouseUp={stopHold}
         decreaseDisabled={value <= 0}
-        increaseDisabled={value >= 5}
+        increaseDisabled={value >= max}
       />
     </div>
   );
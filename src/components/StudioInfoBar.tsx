@@ -11,6 +11,8 @@ import reputationIcon from '@/assets/REPUTATION.png';
 import influenceIcon from '@/assets/IP.png';
 import deadIcon from '@/assets/DEAD.png';
 
+const DEFAULT_TIME_BONUS_MAX = 5;
+
 const LEGAL_GIFTS = [
   { id: 'ALCOHOL', name: 'Whiskey Glennafola 50 Cask Strength' },
   { id: 'EUROPEAN_SPORTCAR', name: 'Lussuria Atlantic Sports Car' },
@@ -84,6 +86,7 @@ interface StudioInfoBarProps {
   resources: Record<string, number>;
   titans: Record<string, number>;
   timeBonuses: Record<string, number>;
+  timeBonusMax: Record<string, number>;
   competitors: CompetitorStudio[];
   onStudioUpdate?: (field: 'budget' | 'cash' | 'reputation' | 'influence', value: number) => void;
   onResourceUpdate?: (resourceId: string, value: number) => void;
@@ -103,6 +106,7 @@ export const StudioInfoBar = memo(function StudioInfoBar({
   resources,
   titans,
   timeBonuses,
+  timeBonusMax,
   competitors,
   onStudioUpdate,
   onResourceUpdate,
@@ -352,6 +356,7 @@ export const StudioInfoBar = memo(function StudioInfoBar({
                   icon={dept.icon}
                   alt={dept.name}
                   value={timeBonuses[dept.id] ?? 0}
+                  max={timeBonusMax[dept.id] ?? DEFAULT_TIME_BONUS_MAX}
                   onChange={(v) => onTimeBonusUpdate?.(dept.id, v)}
                 />
               ))}
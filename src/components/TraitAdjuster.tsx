@@ -1,4 +1,4 @@
-import { useMemo } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import {
   Dialog,
   DialogContent,
@@ -7,7 +7,7 @@ import {
 } from '@/components/ui/dialog';
 import { Tabs, TabsList, TabsTrigger, TabsContent } from '@/components/ui/tabs';
 import { IconButton } from '@/components/IconButton';
-import { Traits, Formatter } from '@/lib';
+import { Traits, Formatter, saveManager, type TraitInfo } from '@/lib';
 
 const MAX_TRAITS = 2;
 const SELECTED_BORDER_COLOR = '#caff96';
@@ -23,6 +23,21 @@ interface TraitAdjusterProps {
 }
 
 export function TraitAdjuster({ open, onOpenChange, traits, onAdd, onRemove }: TraitAdjusterProps) {
+  const [catalogue, setCatalogue] = useState<TraitInfo[]>([]);
+
+  useEffect(() => {
+    if (!open) return;
+    saveManager
+      .getTraitCatalogue()
+      .then(result => setCatalogue(result.entries))
+      .catch(err => console.error('Failed to load trait catalogue:', err));
+  }, [open]);
+
+  const conflicts = useMemo(
+    () => new Map(catalogue.map(t => [t.id, t.conflicts_with] as const)),
+    [catalogue]
+  );
+
   const displayableCurrentTraits = useMemo(
     () => traits.filter(Traits.isDisplayable),
     [traits]
@@ -35,10 +50,9 @@ export function TraitAdjuster({ open, onOpenChange, traits, onAdd, onRemove }: T
     const pairs: Array<[string, string]> = [];
     const nonConflicting: string[] = [];
 
-    Traits.LIST.forEach(trait => {
-      if (seenTraits.has(trait)) return;
-      
-      const conflictTrait = Traits.CONFLICTS[trait];
+    catalogue.forEach(({ id: trait, conflicts_with: conflictTrait }) => {
+      if (seenTraits.has(trait) || !Traits.isDisplayable(trait)) return;
+
       if (conflictTrait && Traits.isDisplayable(conflictTrait)) {
         pairs.push([trait, conflictTrait]);
         seenTraits.add(trait);
@@ -49,7 +63,7 @@ export function TraitAdjuster({ open, onOpenChange, traits, onAdd, onRemove }: T
     });
 
     return { conflictPairs: pairs, nonConflictingTraits: nonConflicting };
-  }, []);
+  }, [catalogue]);
 
   const handleTraitClick = (trait: string) => {
     if (traits.includes(trait)) {
@@ -61,7 +75,7 @@ export function TraitAdjuster({ open, onOpenChange, traits, onAdd, onRemove }: T
 
   const isTraitDisabled = (trait: string): boolean => {
     if (traits.includes(trait)) return false;
-    const conflictTrait = Traits.CONFLICTS[trait];
+    const conflictTrait = conflicts.get(trait);
     if (conflictTrait && traits.includes(conflictTrait)) return true;
     return atMaxTraits;
   };
@@ -1,4 +1,5 @@
 import { useState, useCallback } from 'react';
+import { ErrorUtils } from '@/lib';
 
 interface AsyncActionState {
   loading: boolean;
@@ -25,7 +26,7 @@ export function useAsyncAction(): UseAsyncActionReturn {
     } catch (err) {
       setState({
         loading: false,
-        error: err instanceof Error ? err.message : 'An error occurred',
+        error: ErrorUtils.message(err, 'An error occurred'),
       });
       return undefined;
     }
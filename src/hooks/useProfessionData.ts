@@ -1,6 +1,7 @@
 import { useState, useEffect, useMemo, useCallback, useRef } from 'react';
 import {
   saveManager,
+  ErrorUtils,
   StudioUtils,
   PersonFilters,
   PersonSorter,
@@ -61,7 +62,7 @@ export function useProfessionData(
       setAllPersons(data);
       sortedOrderRef.current = [];
     } catch (err) {
-      setError(err instanceof Error ? err.message : `Failed to load ${labelLower}`);
+      setError(ErrorUtils.message(err, `Failed to load ${labelLower}`));
     } finally {
       setLoading(false);
     }
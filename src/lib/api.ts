@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { open, save } from '@tauri-apps/plugin-dialog';
 import type { 
   SaveInfo, 
@@ -6,11 +7,19 @@ import type {
   PersonUpdate, 
   StudioUpdate, 
   CompetitorStudio, 
-  CompetitorUpdate 
+  CompetitorUpdate,
+  OpenSave,
+  SaveFileChanged,
+  MergeConflict,
+  MergeReport,
+  Catalogue,
+  TraitInfo,
+  DepartmentInfo
 } from './types';
 
 export class SaveManager {
   private currentPath: string | null = null;
+  private currentHandle: string | null = null;
 
   // ───────────────────────────────────────────────────────────────────────────
   // File Operations
@@ -25,14 +34,22 @@ export class SaveManager {
     if (!selected || typeof selected !== 'string') return null;
 
     const info = await invoke<SaveInfo>('load_save_file', { path: selected });
+    const previousHandle = this.currentHandle;
     this.currentPath = selected;
+    this.currentHandle = info.handle;
+
+    // The UI shows one save at a time, so the save it replaces is closed.
+    if (previousHandle && previousHandle !== info.handle) {
+      await this.closeSave(previousHandle);
+    }
 
     return { info };
   }
 
-  async saveSaveFile(): Promise<void> {
+  /** Fails with `MODIFIED_ON_DISK` if the game changed the file since it was loaded, unless `force` is set. */
+  async saveSaveFile(force = false): Promise<void> {
     if (!this.currentPath) throw new Error('No save file loaded');
-    await invoke('save_save_file', { path: this.currentPath });
+    await invoke('save_save_file', { path: this.currentPath, handle: this.currentHandle, force });
   }
 
   async saveSaveFileAs(): Promise<void> {
@@ -43,16 +60,73 @@ export class SaveManager {
 
     if (!selected) return;
 
-    await invoke('save_save_file', { path: selected });
+    await invoke('save_save_file', { path: selected, handle: this.currentHandle });
     this.currentPath = selected;
   }
 
   async reloadSaveFile(): Promise<{ info: SaveInfo } | null> {
     if (!this.currentPath) return null;
     const info = await invoke<SaveInfo>('load_save_file', { path: this.currentPath });
+    this.currentHandle = info.handle;
     return { info };
   }
 
+  /** Merges the editor's changes into the newer version of the save file on disk. */
+  async mergeWithDisk(dryRun = false): Promise<MergeReport> {
+    return invoke<MergeReport>('merge_with_disk', { handle: this.currentHandle, dryRun });
+  }
+
+  /** Summarises the save as it is in memory, including unsaved edits and merges. */
+  async getSaveInfo(): Promise<SaveInfo> {
+    return invoke<SaveInfo>('get_save_info', { handle: this.currentHandle });
+  }
+
+  /**
+   * Puts the editor's values back for merge conflicts, which the merge resolved
+   * with the game's values. The operations run last to first so that removing a
+   * character doesn't shift the pointers of the conflicts before it.
+   */
+  async keepOurValues(conflicts: MergeConflict[]): Promise<void> {
+    const patch = conflicts
+      .map(({ pointer, ours, theirs }) => {
+        if (ours === null || ours === undefined) return { op: 'remove', path: pointer };
+        const op = theirs === null || theirs === undefined ? 'add' : 'replace';
+        return { op, path: pointer, value: ours };
+      })
+      .reverse();
+    await invoke('apply_json_patch', { patch, handle: this.currentHandle });
+  }
+
+  /** Calls `callback` when another program changes the current save's file on disk. */
+  async onSaveFileChanged(callback: (event: SaveFileChanged) => void): Promise<UnlistenFn> {
+    return listen<SaveFileChanged>('save-file-changed', ({ payload }) => {
+      if (payload.handle === this.currentHandle) callback(payload);
+    });
+  }
+
+  async listOpenSaves(): Promise<OpenSave[]> {
+    return invoke<OpenSave[]>('list_open_saves');
+  }
+
+  async closeSave(handle: string): Promise<void> {
+    await invoke('close_save_file', { handle });
+  }
+
+  /** `baseProfession` replaces the role of an executive or department head, which stays with the source save. */
+  async copyPersonBetweenSaves(
+    personId: string,
+    fromHandle: string,
+    toHandle: string,
+    baseProfession?: string
+  ): Promise<string> {
+    return invoke<string>('copy_person_between_saves', {
+      personId: personId.toString(),
+      fromHandle,
+      toHandle,
+      baseProfession,
+    });
+  }
+
   // ───────────────────────────────────────────────────────────────────────────
   // Person Operations
   // ───────────────────────────────────────────────────────────────────────────
@@ -130,6 +204,14 @@ export class SaveManager {
     return invoke<string[]>('get_language_strings', { languageCode });
   }
 
+  async getTraitCatalogue(): Promise<Catalogue<TraitInfo>> {
+    return invoke<Catalogue<TraitInfo>>('get_trait_catalogue');
+  }
+
+  async getDepartmentCatalogue(): Promise<Catalogue<DepartmentInfo>> {
+    return invoke<Catalogue<DepartmentInfo>>('get_department_catalogue');
+  }
+
   async getGamePath(): Promise<string | null> {
     return invoke<string | null>('get_game_path');
   }
@@ -142,6 +224,10 @@ export class SaveManager {
   // State Accessors
   // ───────────────────────────────────────────────────────────────────────────
 
+  getCurrentHandle(): string | null {
+    return this.currentHandle;
+  }
+
   getCurrentPath(): string | null {
     return this.currentPath;
   }
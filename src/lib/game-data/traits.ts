@@ -85,28 +85,8 @@ const TRAIT_ICONS: Record<DisplayableTrait, string> = {
   XENOPHOBE: xenophobeIcon,
 };
 
-const TRAIT_CONFLICTS: Record<string, string> = {
-  HARDWORKING: 'LAZY',
-  LAZY: 'HARDWORKING',
-  DISCIPLINED: 'UNDISCIPLINED',
-  UNDISCIPLINED: 'DISCIPLINED',
-  PERFECTIONIST: 'INDIFFERENT',
-  INDIFFERENT: 'PERFECTIONIST',
-  HOTHEADED: 'CALM',
-  CALM: 'HOTHEADED',
-  DEMANDING: 'MODEST',
-  MODEST: 'DEMANDING',
-  ARROGANT: 'SIMPLE',
-  SIMPLE: 'ARROGANT',
-  HEARTBREAKER: 'CHASTE',
-  CHASTE: 'HEARTBREAKER',
-  CHEERY: 'MELANCHOLIC',
-  MELANCHOLIC: 'CHEERY',
-};
-
 export class Traits {
   static readonly LIST = DISPLAYABLE_TRAITS;
-  static readonly CONFLICTS = TRAIT_CONFLICTS;
 
   static getIcon(trait: string): string | undefined {
     return TRAIT_ICONS[trait as DisplayableTrait];
@@ -116,10 +96,6 @@ export class Traits {
     return (DISPLAYABLE_TRAITS as readonly string[]).includes(trait);
   }
 
-  static getConflict(trait: string): string | undefined {
-    return TRAIT_CONFLICTS[trait];
-  }
-
   static getAll(): readonly DisplayableTrait[] {
     return DISPLAYABLE_TRAITS;
   }
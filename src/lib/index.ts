@@ -10,16 +10,25 @@ export type {
   WhiteTag,
   OverallValue,
   SaveInfo,
+  OpenSave,
+  SaveFileChanged,
+  MergeConflict,
+  MergeReport,
   StudioUpdate,
   CompetitorStudio,
   CompetitorUpdate,
+  Catalogue,
+  TraitInfo,
+  DepartmentInfo,
+  AppError,
+  AppErrorCode,
 } from './types';
 
 // API
 export { SaveManager, saveManager } from './api';
 
 // Utils (re-export from submodule)
-export { DateUtils, Formatter, ValueSteppers } from './utils';
+export { DateUtils, ErrorUtils, Formatter, ValueSteppers } from './utils';
 
 // Person utilities (re-export from submodule)
 export {
@@ -62,11 +62,15 @@ export interface PersonUpdate {
   com?: number | null;
   addTrait?: string;
   removeTrait?: string;
+  resolveTraitConflicts?: boolean;
   addGenre?: string;
   removeGenre?: string;
+  genreValues?: Record<string, number>;
   portraitBaseId?: number;
   birthYear?: number;
   isShady?: boolean;
+  contractMonthlySalary?: number;
+  contractInitialFee?: number;
   bonusCardMoney?: number;
   bonusCardInfluencePoints?: number;
 }
@@ -96,6 +100,7 @@ export interface OverallValue {
 // ─────────────────────────────────────────────────────────────────────────────
 
 export interface SaveInfo {
+  handle: string;
   current_date: string;
   player_studio_name: string;
   actors_count: number;
@@ -117,6 +122,31 @@ export interface SaveInfo {
   studio_logo_id: number;
 }
 
+export interface OpenSave {
+  handle: string;
+  path: string;
+  active: boolean;
+}
+
+export interface SaveFileChanged {
+  handle: string;
+  path: string;
+}
+
+export interface MergeConflict {
+  path: string;
+  pointer: string;
+  base: unknown;
+  ours: unknown;
+  theirs: unknown;
+}
+
+export interface MergeReport {
+  dry_run: boolean;
+  conflicts: MergeConflict[];
+  checkpoint: string | null;
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Studio Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -126,6 +156,8 @@ export interface StudioUpdate {
   cash?: number;
   reputation?: number;
   influence?: number;
+  studioName?: string;
+  studioLogoId?: number;
 }
 
 export interface CompetitorStudio {
@@ -142,3 +174,45 @@ export interface CompetitorUpdate {
   ip?: number;
   budgetCheatsRemaining?: number;
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Catalogue Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/** Game definitions, read from the install or the backend's builtin tables. */
+export interface Catalogue<T> {
+  entries: T[];
+  from_game_data: boolean;
+}
+
+export interface TraitInfo {
+  id: string;
+  conflicts_with: string | null;
+}
+
+export interface DepartmentInfo {
+  id: string;
+  name: string;
+  max_time_bonus: number;
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Error Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type AppErrorCode =
+  | 'NO_SAVE_LOADED'
+  | 'GAME_NOT_FOUND'
+  | 'MISSING_FIELD'
+  | 'NOT_FOUND'
+  | 'PARSE'
+  | 'MODIFIED_ON_DISK'
+  | 'IO'
+  | 'INVALID';
+
+/** Error payload rejected by backend commands. */
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details: Record<string, unknown> | null;
+}
@@ -0,0 +1,18 @@
+import type { AppError } from '../types';
+
+export class ErrorUtils {
+  static isAppError(err: unknown): err is AppError {
+    return (
+      typeof err === 'object' &&
+      err !== null &&
+      typeof (err as AppError).code === 'string' &&
+      typeof (err as AppError).message === 'string'
+    );
+  }
+
+  static message(err: unknown, fallback: string): string {
+    if (ErrorUtils.isAppError(err) || err instanceof Error) return err.message;
+    if (typeof err === 'string') return err;
+    return fallback;
+  }
+}
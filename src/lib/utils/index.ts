@@ -1,3 +1,4 @@
 export { DateUtils } from './date';
 export { Formatter } from './format';
 export { ValueSteppers } from './value-steppers';
+export { ErrorUtils } from './error';